@@ -1,5 +1,10 @@
 use super::Graph;
-use itertools::Itertools;
+
+/// Subset of the vertices of a bag, with bit `i` standing for the `i`-th vertex of the sorted bag.
+pub type Mask = u128;
+
+/// Largest number of vertices in a bag whose subsets can be represented as [`Mask`].
+pub const MAX_BAG_SIZE: usize = Mask::BITS as usize;
 
 #[derive(Debug, Clone)]
 pub struct Bag {
@@ -19,19 +24,96 @@ impl Bag {
         }
     }
 
-    pub fn set_vertices(&mut self, vertex_str: &str, graph: &Graph) {
-        let splits = vertex_str.split(";").collect::<Vec<&str>>();
-        if splits.is_empty() { return; }
-        
-        self.vertices = splits.iter().map(|vs| *graph.get_vertex_idx(vs.trim()).unwrap()).collect();
-        self.vertices.sort();
+    /// Sets the vertices of the bag from a `;`-separated list of vertex names.
+    /// Returns the first name that is not a vertex of `graph` as error.
+    pub fn set_vertices<'a>(&mut self, vertex_str: &'a str, graph: &Graph) -> Result<(), &'a str> {
+        let mut vertices = Vec::new();
+        for vs in vertex_str.split(";") {
+            match graph.get_vertex_idx(vs.trim()) {
+                Some(idx) => vertices.push(*idx),
+                None => return Err(vs.trim())
+            }
+        }
+        vertices.sort();
+        self.vertices = vertices;
+        Ok(())
     }
 
     pub fn vertices(&self) -> &Vec<usize> {
         &self.vertices
     }
 
-    pub fn get_powerset(&self) -> Vec<Vec<usize>> {
-        (0..self.vertices.len() + 1).flat_map(|i| self.vertices.iter().map(|x| *x).combinations(i)).collect()
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Position of `vertex` within the (sorted) bag, i.e. its bit in subset masks.
+    pub fn position(&self, vertex: usize) -> Option<usize> {
+        self.vertices.binary_search(&vertex).ok()
+    }
+
+    /// Vertices of the subset given by `mask`.
+    pub fn subset(&self, mask: Mask) -> impl Iterator<Item = usize> + '_ {
+        self.vertices.iter().enumerate().filter(move |(i, _)| mask & (1 << i) != 0).map(|(_, v)| *v)
+    }
+
+    /// Mask of the subset of vertices satisfying `predicate`.
+    pub fn mask_where(&self, predicate: impl Fn(usize) -> bool) -> Mask {
+        self.vertices.iter().enumerate().filter(|(_, v)| predicate(**v)).fold(0, |mask, (i, _)| mask | (1 << i))
+    }
+
+    /// Total weight of the subset given by `mask`.
+    pub fn weight_of(&self, mask: Mask, graph: &Graph) -> usize {
+        self.subset(mask).map(|v| graph.get_weight(v)).sum()
     }
-}
\ No newline at end of file
+
+    /// Masks of all independent subsets of the bag, generated by branching on its vertices in order.
+    pub fn independent_subsets(&self, graph: &Graph) -> Vec<Mask> {
+        let adjacency = self.adjacency_masks(graph);
+        let mut subsets = Vec::new();
+        let mut stack = vec![(0, 0)];   // (next vertex position, subset so far)
+        while let Some((i, mask)) = stack.pop() {
+            if i == self.len() {
+                subsets.push(mask);
+                continue;
+            }
+            stack.push((i + 1, mask));  // Skip vertex i
+            let with_i = mask | (1 << i);
+            if adjacency[i] & with_i == 0 {
+                stack.push((i + 1, with_i));    // Take vertex i
+            }
+        }
+        subsets
+    }
+
+    /// Number of independent subsets of the bag, i.e. the size of a DP table for it, counted without enumerating
+    /// the subsets one by one: a vertex without neighbors among the remaining vertices doubles the count.
+    pub fn count_independent_subsets(&self, graph: &Graph) -> f64 {
+        fn count(adjacency: &[Mask], remaining: Mask) -> f64 {
+            if remaining == 0 { return 1.0; }
+
+            let v = remaining.trailing_zeros() as usize;
+            let without_v = remaining & !(1 << v);
+            if adjacency[v] & without_v == 0 {
+                2.0 * count(adjacency, without_v)
+            } else {
+                count(adjacency, without_v) + count(adjacency, without_v & !adjacency[v])
+            }
+        }
+
+        if self.len() > MAX_BAG_SIZE {
+            return 2f64.powi(self.len() as i32);    // Subsets cannot be given as masks, bound by all subsets
+        }
+        let all = if self.len() == MAX_BAG_SIZE { Mask::MAX } else { (1 << self.len()) - 1 };
+        count(&self.adjacency_masks(graph), all)
+    }
+
+    /// For every vertex of the bag, the mask of its neighbors within the bag.
+    pub fn adjacency_masks(&self, graph: &Graph) -> Vec<Mask> {
+        self.vertices.iter().map(|v| self.mask_where(|u| graph.neighbors(*v).contains(&u))).collect()
+    }
+}
@@ -0,0 +1,71 @@
+use rustc_hash::FxHashMap;
+
+use super::Mask;
+
+/// DP table of a node, mapping the independent subsets of its bag (given as masks) to the weight of
+/// a maximum independent set in the subtree that agrees with the subset on the bag.
+/// Subsets that are not independent have no entry.
+#[derive(Debug)]
+pub enum DpTable {
+    /// Flat array over all subsets, used if most subsets are independent and the bag is small enough to index them.
+    Dense(Vec<Option<usize>>),
+    /// Hash map holding only the independent subsets.
+    Sparse(FxHashMap<Mask, usize>)
+}
+
+impl Default for DpTable {
+    fn default() -> Self {
+        DpTable::Sparse(FxHashMap::default())
+    }
+}
+
+impl DpTable {
+    /// Creates an empty table for `num_entries` independent subsets of a bag with `bag_size` vertices.
+    pub fn new(bag_size: usize, num_entries: usize) -> Self {
+        // A dense entry needs 16 bytes, a sparse one about twice that including hash map overhead
+        if bag_size < usize::BITS as usize && num_entries.saturating_mul(2) >= 1 << bag_size {
+            DpTable::Dense(vec![None; 1 << bag_size])
+        } else {
+            let mut map = FxHashMap::default();
+            map.reserve(num_entries);
+            DpTable::Sparse(map)
+        }
+    }
+
+    pub fn get(&self, mask: Mask) -> Option<usize> {
+        match self {
+            DpTable::Dense(entries) => entries[mask as usize],
+            DpTable::Sparse(entries) => entries.get(&mask).copied()
+        }
+    }
+
+    pub fn insert(&mut self, mask: Mask, weight: usize) {
+        match self {
+            DpTable::Dense(entries) => entries[mask as usize] = Some(weight),
+            DpTable::Sparse(entries) => { entries.insert(mask, weight); }
+        }
+    }
+
+    /// Number of entries, i.e. independent subsets.
+    pub fn len(&self) -> usize {
+        match self {
+            DpTable::Dense(entries) => entries.iter().flatten().count(),
+            DpTable::Sparse(entries) => entries.len()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            DpTable::Dense(entries) => entries.iter().all(|e| e.is_none()),
+            DpTable::Sparse(entries) => entries.is_empty()
+        }
+    }
+
+    /// Iterates over all (mask, weight) entries.
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (Mask, usize)> + '_> {
+        match self {
+            DpTable::Dense(entries) => Box::new(entries.iter().enumerate().filter_map(|(mask, w)| w.map(|w| (mask as Mask, w)))),
+            DpTable::Sparse(entries) => Box::new(entries.iter().map(|(mask, w)| (*mask, *w)))
+        }
+    }
+}
@@ -1,7 +1,10 @@
-use std::fs::read_to_string;
-
 use rustc_hash::FxHashMap;
 
+use super::GraphBuilder;
+use crate::error::{Result, TdIsError};
+use crate::formats::read_input;
+
+/// A vertex weighted, undirected graph
 #[derive(Debug)]
 pub struct Graph {
     vertex_indices: FxHashMap<String, usize>,
@@ -11,91 +14,95 @@ pub struct Graph {
 }
 
 impl Graph {
-    pub fn new(path: &str) -> Self {
+    /// Parses a graph from a CSV file with `vertex,,weight` and `vertex,vertex` lines.
+    pub fn new(path: &str) -> Result<Self> {
         eprintln!("Parsing graph from {}", path);
-        let mut vertex_indices = FxHashMap::default();
-        let mut vertex_names = FxHashMap::default();
-        let mut weights = Vec::new();
-        let mut edges = Vec::new();
-        for line in read_to_string(path).unwrap().lines() {
+        Self::from_csv(&read_input(path)?, path)
+    }
+
+    /// Parses a graph from CSV content, see [`Graph::new`]. `path` is only used in error messages.
+    pub fn from_csv(content: &str, path: &str) -> Result<Self> {
+        let mut builder = GraphBuilder::new();
+        for (line_idx, line) in content.lines().enumerate() {
             let splits = line.split(",").collect::<Vec<&str>>();            
             if splits.is_empty() { continue; }
             
             let vertex_name = splits[0].trim();
-            if vertex_name.is_empty() { continue; } // No empty vertexnames allowed
-
-            if !vertex_indices.contains_key(vertex_name) {
-                vertex_indices.insert(vertex_name.to_string(), weights.len());
-                vertex_names.insert(weights.len(), vertex_name.to_string());
-                weights.push(0);
-                edges.push(Vec::new());
+            if vertex_name.is_empty() {     // No empty vertexnames allowed
+                if splits.iter().all(|s| s.trim().is_empty()) { continue; }
+                return Err(TdIsError::MalformedLine { path: path.to_string(), line: line_idx + 1, content: line.to_string() });
             }
 
-            let vertex_idx = *vertex_indices.get(vertex_name).unwrap();
+            let vertex_idx = builder.get_or_add_vertex(vertex_name);
 
             if splits.len() < 2 { continue; }
 
             // Edge entry
             let second_vertex_name = splits[1].trim();
             if !second_vertex_name.is_empty() {
-                // Add second vertex if first occurrence
-                if !vertex_indices.contains_key(second_vertex_name) {
-                    vertex_indices.insert(second_vertex_name.to_string(), weights.len());
-                    vertex_names.insert(weights.len(), second_vertex_name.to_string());
-                    weights.push(0);
-                    edges.push(Vec::new());
-                }
-                let second_vertex_idx = *vertex_indices.get(second_vertex_name).unwrap();
-                // Add edge
-                edges[vertex_idx].push(second_vertex_idx);
-                edges[second_vertex_idx].push(vertex_idx);
+                let second_vertex_idx = builder.get_or_add_vertex(second_vertex_name);
+                builder.add_edge(vertex_idx, second_vertex_idx);
 
                 continue;   // We don't need edge labels
             }
 
             if splits.len() < 3 || splits[2].trim().is_empty() { continue; }
 
-            match splits[2].parse::<usize>() {
-                Ok(res) => weights[vertex_indices[vertex_name]] = res,
-                Err(e) => {
-                    eprintln!("Label not a positive integer weight: {}", e);                    
-                }
+            match splits[2].trim().parse::<usize>() {
+                Ok(res) => builder.set_weight(vertex_idx, res),
+                Err(_) => return Err(TdIsError::BadWeight { path: path.to_string(), line: line_idx + 1, value: splits[2].trim().to_string() })
             }
         }
         
+        Ok(builder.build())
+    }
+
+    pub(super) fn from_parts(vertex_indices: FxHashMap<String, usize>, vertex_names: FxHashMap<usize, String>, edges: Vec<Vec<usize>>, weights: Vec<usize>) -> Self {
         Graph {
             vertex_indices,
             vertex_names,
-            weights,
-            edges
+            edges,
+            weights
         }
     }
 
+    /// Number of vertices.
     pub fn size(&self) -> usize {
         self.weights.len()
     }
 
+    /// Index of the vertex with the given name.
     pub fn get_vertex_idx(&self, vertex_name: &str) -> Option<&usize> {
         self.vertex_indices.get(vertex_name)
     }
 
+    /// Name of the vertex with the given index.
     pub fn get_vertex_name(&self, idx: usize) -> &String {
         self.vertex_names.get(&idx).unwrap()
     }
 
+    /// Weight of the vertex with the given index.
     pub fn get_weight(&self, idx: usize) -> usize {
         self.weights[idx]
     }
 
-    pub fn get_weight_of_set(&self, set: &Vec<usize>) -> usize {
+    /// Indices of the neighbors of the vertex with the given index.
+    pub fn neighbors(&self, idx: usize) -> &Vec<usize> {
+        &self.edges[idx]
+    }
+
+    /// Total weight of a set of vertices.
+    pub fn get_weight_of_set(&self, set: &[usize]) -> usize {
         set.iter().fold(0, |acc, i| acc + self.weights[*i])
     }
 
-    pub fn is_neighbor(&self, vertex: usize, others: &Vec<usize>) -> bool {
+    /// Whether `vertex` is adjacent to any vertex in `others`.
+    pub fn is_neighbor(&self, vertex: usize, others: &[usize]) -> bool {
         self.edges[vertex].iter().any(|x| others.contains(x))
     }
 
-    pub fn is_is(&self, vertices: &Vec<usize>) -> bool {
+    /// Whether `vertices` form an independent set.
+    pub fn is_is(&self, vertices: &[usize]) -> bool {
         vertices.iter().all(|x| !self.is_neighbor(*x, vertices))
     }
 }
\ No newline at end of file
@@ -0,0 +1,79 @@
+use rustc_hash::FxHashMap;
+
+use super::Graph;
+
+/// Incrementally builds a [`Graph`] from vertices and edges.
+#[derive(Debug, Default)]
+pub struct GraphBuilder {
+    vertex_indices: FxHashMap<String, usize>,
+    vertex_names: FxHashMap<usize, String>,
+    edges: Vec<Vec<usize>>,
+    weights: Vec<usize>
+}
+
+impl GraphBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a vertex with the given name and weight and returns its index.
+    /// If a vertex with this name already exists, only its weight is updated.
+    pub fn add_vertex(&mut self, name: &str, weight: usize) -> usize {
+        let idx = self.get_or_add_vertex(name);
+        self.weights[idx] = weight;
+        idx
+    }
+
+    /// Returns the index of the vertex with the given name, adding it with weight 0 on first occurrence.
+    pub fn get_or_add_vertex(&mut self, name: &str) -> usize {
+        if let Some(idx) = self.vertex_indices.get(name) {
+            return *idx;
+        }
+
+        let idx = self.weights.len();
+        self.vertex_indices.insert(name.to_string(), idx);
+        self.vertex_names.insert(idx, name.to_string());
+        self.weights.push(0);
+        self.edges.push(Vec::new());
+        idx
+    }
+
+    /// Sets the weight of the vertex with index `idx`.
+    ///
+    /// # Panics
+    /// Panics if no vertex with index `idx` has been added.
+    pub fn set_weight(&mut self, idx: usize, weight: usize) {
+        self.check_index(idx);
+        self.weights[idx] = weight;
+    }
+
+    /// Adds an undirected edge between the vertices with indices `u` and `v`. Duplicate edges are ignored.
+    ///
+    /// # Panics
+    /// Panics if no vertex with index `u` or `v` has been added.
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        self.check_index(u);
+        self.check_index(v);
+        if self.edges[u].contains(&v) { return; }
+
+        self.edges[u].push(v);
+        if u != v {
+            self.edges[v].push(u);
+        }
+    }
+
+    /// Number of vertices added so far.
+    pub fn size(&self) -> usize {
+        self.weights.len()
+    }
+
+    fn check_index(&self, idx: usize) {
+        if idx >= self.size() {
+            panic!("Vertex index {} out of range, only {} vertices have been added", idx, self.size());
+        }
+    }
+
+    pub fn build(self) -> Graph {
+        Graph::from_parts(self.vertex_indices, self.vertex_names, self.edges, self.weights)
+    }
+}
@@ -1,31 +1,44 @@
-use std::fs::read_to_string;
-
-use super::{Bag, Graph};
-use rustc_hash::FxHashMap;
+use super::{Bag, Graph, TdViolation};
+use crate::error::{Result, TdIsError};
+use crate::formats::read_input;
+use rustc_hash::{FxHashMap, FxHashSet};
 
+/// A (not necessarily nice) tree decomposition given as bags and an adjacency list over the bags
 #[derive(Debug)]
 pub struct InputTreeDecomposition {
     bags: Vec<Bag>,
+    bag_names: Vec<String>,
     edges: Vec<Vec<usize>>
 }
 
 impl InputTreeDecomposition {
-    pub fn new(path: &str, graph: &Graph) -> Self {
+    /// Parses a tree decomposition of `graph` from a CSV file with `bag,,v1;v2;...` and `bag,bag` lines.
+    pub fn new(path: &str, graph: &Graph) -> Result<Self> {
         eprintln!("Parsing treedecomposition from {}", path);
+        Self::from_csv(&read_input(path)?, path, graph)
+    }
+
+    /// Parses a tree decomposition of `graph` from CSV content, see [`Self::new`]. `path` is only used in error messages.
+    pub fn from_csv(content: &str, path: &str, graph: &Graph) -> Result<Self> {
         let mut bag_indices = FxHashMap::default();
         let mut bags = Vec::new();
+        let mut bag_names = Vec::new();
         let mut edges = Vec::new();
-        for line in read_to_string(path).unwrap().lines() {
+        for (line_idx, line) in content.lines().enumerate() {
             let splits = line.split(",").collect::<Vec<&str>>();
             if splits.is_empty() { continue; }
             
             let bag_name = splits[0].trim();
-            if bag_name.is_empty() { continue; }    // No empty bag names allowed
+            if bag_name.is_empty() {    // No empty bag names allowed
+                if splits.iter().all(|s| s.trim().is_empty()) { continue; }
+                return Err(TdIsError::MalformedLine { path: path.to_string(), line: line_idx + 1, content: line.to_string() });
+            }
 
             // Add bag if first occurrence
             if !bag_indices.contains_key(bag_name) {
                 bag_indices.insert(bag_name.to_string(), bags.len());
                 bags.push(Bag::new_empty());
+                bag_names.push(bag_name.to_string());
                 edges.push(Vec::new());
             }
 
@@ -40,25 +53,83 @@ impl InputTreeDecomposition {
                 if !bag_indices.contains_key(second_bag_name) {
                     bag_indices.insert(second_bag_name.to_string(), bags.len());
                     bags.push(Bag::new_empty());
+                    bag_names.push(second_bag_name.to_string());
                     edges.push(Vec::new());
                 }
                 let second_bag_idx = *bag_indices.get(second_bag_name).unwrap();
                 // Add edge
-                edges[bag_idx].push(second_bag_idx);
-                edges[second_bag_idx].push(bag_idx);
+                if !edges[bag_idx].contains(&second_bag_idx) {
+                    edges[bag_idx].push(second_bag_idx);
+                    if bag_idx != second_bag_idx {
+                        edges[second_bag_idx].push(bag_idx);
+                    }
+                }
 
                 continue;   // We don't need edge labels
             }
             
             if splits.len() < 3 || splits[2].trim().is_empty() { continue; }    // Empty bag?
 
-            bags[bag_idx].set_vertices(splits[2], graph)
+            if let Err(vertex) = bags[bag_idx].set_vertices(splits[2], graph) {
+                return Err(TdIsError::UnknownVertex { path: path.to_string(), line: line_idx + 1, bag: bag_name.to_string(), vertex: vertex.to_string() });
+            }
         }
         
-        InputTreeDecomposition {
+        Ok(InputTreeDecomposition {
             bags,
+            bag_names,
             edges
+        })
+    }
+
+    /// Creates a tree decomposition of `graph` from bags given as lists of graph vertex indices and
+    /// an adjacency list over the bag indices. Missing reverse edges are added.
+    pub fn from_bags(bags: Vec<Vec<usize>>, edges: Vec<Vec<usize>>, graph: &Graph) -> Result<Self> {
+        let bag_names = (0..bags.len()).map(|i| i.to_string()).collect();
+        Self::from_named_bags(bag_names, bags, edges, graph)
+    }
+
+    /// Like [`Self::from_bags`], but with a name for every bag that is used in diagnostics.
+    pub fn from_named_bags(bag_names: Vec<String>, bags: Vec<Vec<usize>>, mut edges: Vec<Vec<usize>>, graph: &Graph) -> Result<Self> {
+        if bag_names.len() != bags.len() {
+            return Err(TdIsError::InvalidTd(format!("{} bag names given for {} bags", bag_names.len(), bags.len())));
+        }
+        if edges.len() > bags.len() {
+            return Err(TdIsError::InvalidTd(format!("adjacency list has {} entries, but there are only {} bags", edges.len(), bags.len())));
+        }
+        for (i, bag) in bags.iter().enumerate() {
+            if let Some(v) = bag.iter().find(|v| **v >= graph.size()) {
+                return Err(TdIsError::InvalidTd(format!("bag {} contains vertex index {}, but the graph only has {} vertices", i, v, graph.size())));
+            }
+        }
+        for (i, neighbors) in edges.iter().enumerate() {
+            if let Some(j) = neighbors.iter().find(|j| **j >= bags.len()) {
+                return Err(TdIsError::InvalidTd(format!("bag {} is adjacent to bag {}, but there are only {} bags", i, j, bags.len())));
+            }
+        }
+
+        edges.resize(bags.len(), Vec::new());
+        for i in 0..edges.len() {
+            for j in edges[i].clone() {
+                if !edges[j].contains(&i) {
+                    edges[j].push(i);
+                }
+            }
+        }
+        for neighbors in edges.iter_mut() {
+            neighbors.sort();
+            neighbors.dedup();
         }
+
+        Ok(InputTreeDecomposition {
+            bag_names,
+            bags: bags.into_iter().map(|mut vertices| {
+                vertices.sort();
+                vertices.dedup();
+                Bag::new(vertices)
+            }).collect(),
+            edges
+        })
     }
 
     pub fn edges(&self) -> &Vec<Vec<usize>> {
@@ -68,8 +139,202 @@ impl InputTreeDecomposition {
     pub fn get_bag(&self, idx: usize) -> &Bag {
         &self.bags[idx]
     }
+
+    /// Name of the bag with the given index (its index for tree decompositions created with [`Self::from_bags`]).
+    pub fn get_bag_name(&self, idx: usize) -> &String {
+        &self.bag_names[idx]
+    }
     
     pub fn len(&self) -> usize {
         self.bags.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.bags.is_empty()
+    }
+
+    /// Size of the largest bag minus one (0 for a decomposition without bags).
+    pub fn width(&self) -> usize {
+        self.bags.iter().map(|bag| bag.len()).max().unwrap_or(1).saturating_sub(1)
+    }
+
+    /// Estimated cost of the dynamic program if the tree containing a bag is rooted at this bag, for every bag:
+    /// the sum of the table sizes over the nodes of the nice tree decomposition built from this rooting (without the
+    /// dummy root node). A table holds the independent subsets of its bag, which are counted for the bags of the
+    /// decomposition. The bags of the introduce and forget nodes in between are subsets of these, their tables are
+    /// bounded by `min(2^|bag|, size of the table of the input bag)`. Computed for all bags at once by moving the root
+    /// along the edges.
+    pub fn rooting_costs(&self, graph: &Graph) -> Vec<f64> {
+        let independent_subsets = self.bags.iter().map(|bag| bag.count_independent_subsets(graph)).collect::<Vec<f64>>();
+        // Bound for a subset of `size` vertices of bag `b`
+        let table_size = |b: usize, size: usize| 2f64.powi(size as i32).min(independent_subsets[b]);
+        // Leaf or join nodes of a bag with the given number of children
+        let node_cost = |b: usize, children: usize| match children {
+            0 => independent_subsets[b],
+            _ => (children - 1) as f64 * independent_subsets[b]
+        };
+        // Introduce and forget nodes between a bag and its child
+        let chain_cost = |b: usize, child: usize| {
+            let common = self.bags[b].vertices().iter().filter(|v| self.bags[child].position(**v).is_some()).count();
+            (common + 1..=self.bags[b].len()).map(|size| table_size(b, size)).sum::<f64>()
+                + (common..self.bags[child].len()).map(|size| table_size(child, size)).sum::<f64>()
+        };
+
+        let mut costs = vec![0.0; self.len()];
+        let mut parent = vec![usize::MAX; self.len()];
+        let mut reached = vec![false; self.len()];
+        for root in 0..self.len() {
+            if reached[root] { continue; }
+
+            reached[root] = true;
+            let mut order = vec![root];
+            let mut i = 0;
+            while i < order.len() {
+                let b = order[i];
+                for neighbor in self.edges[b].iter().filter(|n| !reached[**n]).copied().collect::<Vec<usize>>() {
+                    reached[neighbor] = true;
+                    parent[neighbor] = b;
+                    order.push(neighbor);
+                }
+                i += 1;
+            }
+
+            costs[root] = node_cost(root, self.edges[root].len());
+            for b in order.iter().skip(1) {
+                costs[root] += node_cost(*b, self.edges[*b].len() - 1) + chain_cost(parent[*b], *b);
+            }
+            for b in order.into_iter().skip(1) {
+                // Move the root from the parent to b
+                let p = parent[b];
+                costs[b] = costs[p] - chain_cost(p, b) + chain_cost(b, p)
+                    - node_cost(p, self.edges[p].len()) + node_cost(p, self.edges[p].len() - 1)
+                    - node_cost(b, self.edges[b].len() - 1) + node_cost(b, self.edges[b].len());
+            }
+        }
+        costs
+    }
+
+    /// Checks whether this is a tree decomposition of `graph` and returns all violations found:
+    /// every vertex and edge has to be contained in a bag, the bags containing a vertex have to
+    /// form a connected subtree and the bags together with their edges have to form a forest.
+    pub fn validate(&self, graph: &Graph) -> Vec<TdViolation> {
+        let mut violations = Vec::new();
+        let mut occurrences = vec![Vec::new(); graph.size()];
+        for (i, bag) in self.bags.iter().enumerate() {
+            bag.vertices().iter().for_each(|v| occurrences[*v].push(i));
+        }
+
+        // Vertex and edge coverage
+        for (v, bags) in occurrences.iter().enumerate() {
+            if bags.is_empty() {
+                violations.push(TdViolation::UncoveredVertex(graph.get_vertex_name(v).clone()));
+                continue;
+            }
+            for u in graph.neighbors(v).iter().filter(|u| **u > v) {
+                if !bags.iter().any(|b| self.bags[*b].vertices().binary_search(u).is_ok()) {
+                    violations.push(TdViolation::UncoveredEdge(graph.get_vertex_name(v).clone(), graph.get_vertex_name(*u).clone()));
+                }
+            }
+        }
+
+        // Bags containing a vertex have to be connected
+        let mut component = vec![usize::MAX; self.len()];
+        for (v, bags) in occurrences.iter().enumerate() {
+            let mut num_components = 0;
+            for start in bags.iter() {
+                if component[*start] == v { continue; }
+                num_components += 1;
+                component[*start] = v;
+                let mut stack = vec![*start];
+                while let Some(b) = stack.pop() {
+                    for n in self.edges[b].iter() {
+                        if component[*n] != v && self.bags[*n].vertices().binary_search(&v).is_ok() {
+                            component[*n] = v;
+                            stack.push(*n);
+                        }
+                    }
+                }
+            }
+            if num_components > 1 {
+                let bag_names = bags.iter().map(|b| self.bag_names[*b].clone()).collect();
+                violations.push(TdViolation::DisconnectedVertex(graph.get_vertex_name(v).clone(), bag_names));
+            }
+        }
+
+        // Bag edges must not close a cycle
+        let mut parent = (0..self.len()).collect::<Vec<usize>>();
+        fn find(parent: &mut [usize], mut x: usize) -> usize {
+            while parent[x] != x {
+                parent[x] = parent[parent[x]];
+                x = parent[x];
+            }
+            x
+        }
+        for (i, neighbors) in self.edges.iter().enumerate() {
+            for j in neighbors.iter().filter(|j| **j >= i) {
+                let (root_i, root_j) = (find(&mut parent, i), find(&mut parent, *j));
+                if root_i == root_j {
+                    violations.push(TdViolation::Cycle(self.bag_names[i].clone(), self.bag_names[*j].clone()));
+                } else {
+                    parent[root_i] = root_j;
+                }
+            }
+        }
+
+        violations
+    }
+
+    /// Contracts every bag that is a subset of a neighboring bag into this neighbor, which removes the
+    /// introduce/forget chains and leaves such bags would turn into. Empty bags without neighbors are dropped
+    /// unless no other bag remains. Fails if this is not a tree decomposition of `graph`.
+    pub fn simplify(&self, graph: &Graph) -> Result<Self> {
+        let violations = self.validate(graph);
+        if !violations.is_empty() {
+            return Err(TdIsError::InvalidTd(violations.iter().map(|v| v.to_string()).collect::<Vec<String>>().join("; ")));
+        }
+
+        let is_subset = |a: usize, b: usize| self.bags[a].vertices().iter().all(|v| self.bags[b].position(*v).is_some());
+        let mut neighbors = self.edges.iter().map(|n| n.iter().copied().collect::<FxHashSet<usize>>()).collect::<Vec<_>>();
+        let mut alive = vec![true; self.len()];
+        let mut todo = (0..self.len()).rev().collect::<Vec<usize>>();
+        while let Some(a) = todo.pop() {
+            if !alive[a] { continue; }
+            let Some(b) = neighbors[a].iter().copied().filter(|b| is_subset(a, *b)).min() else { continue };
+
+            // Contract a into b, b keeps its bag
+            alive[a] = false;
+            for c in std::mem::take(&mut neighbors[a]) {
+                neighbors[c].remove(&a);
+                if c != b {
+                    neighbors[c].insert(b);
+                    neighbors[b].insert(c);
+                    todo.push(c);
+                }
+            }
+            todo.push(b);
+        }
+
+        let mut num_alive = alive.iter().filter(|a| **a).count();
+        for a in 0..self.len() {
+            if num_alive > 1 && alive[a] && self.bags[a].is_empty() && neighbors[a].is_empty() {
+                alive[a] = false;
+                num_alive -= 1;
+            }
+        }
+
+        let kept = (0..self.len()).filter(|a| alive[*a]).collect::<Vec<usize>>();
+        let mut new_idx = vec![usize::MAX; self.len()];
+        for (i, a) in kept.iter().enumerate() {
+            new_idx[*a] = i;
+        }
+        Ok(InputTreeDecomposition {
+            bags: kept.iter().map(|a| self.bags[*a].clone()).collect(),
+            bag_names: kept.iter().map(|a| self.bag_names[*a].clone()).collect(),
+            edges: kept.iter().map(|a| {
+                let mut edges = neighbors[*a].iter().map(|b| new_idx[*b]).collect::<Vec<usize>>();
+                edges.sort();
+                edges
+            }).collect()
+        })
+    }
 }
@@ -1,13 +1,21 @@
 mod graph;
+mod graph_builder;
 mod input_tree_decomposition;
 mod tree_decomposition;
 mod bag;
+mod td_violation;
 mod node;
+mod dp_table;
+mod nice_td_file;
 
-use input_tree_decomposition::InputTreeDecomposition;
-use bag::Bag;
+use bag::{Bag, Mask};
 use node::NodeType;
 use node::Node;
+use dp_table::DpTable;
 
+pub(crate) use bag::MAX_BAG_SIZE;
+pub use input_tree_decomposition::InputTreeDecomposition;
 pub use tree_decomposition::TreeDecomposition;
+pub use td_violation::TdViolation;
 pub use graph::Graph;
+pub use graph_builder::GraphBuilder;
@@ -0,0 +1,188 @@
+//! Saving and loading of constructed nice tree decompositions as JSON documents:
+//!
+//! ```json
+//! {"trees": [{"nodes": [{"type": "Root", "bag": [], "next": null, "prev": [1]},
+//!                       {"type": {"Introduce": "a"}, "bag": ["a", "b"], "next": 0, "prev": [2]}, ...],
+//!             "leaves": [3]}]}
+//! ```
+//!
+//! Vertices are referred to by name, so a saved decomposition can be reused for a graph with other weights.
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+use crate::error::{Result, TdIsError};
+use crate::formats::read_input;
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NiceTdFile {
+    trees: Vec<TreeEntry>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TreeEntry {
+    nodes: Vec<NodeEntry>,
+    leaves: Vec<usize>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NodeEntry {
+    #[serde(rename = "type")]
+    node_type: NodeTypeEntry,
+    bag: Vec<String>,
+    next: Option<usize>,
+    prev: Vec<usize>
+}
+
+#[derive(Serialize, Deserialize)]
+enum NodeTypeEntry {
+    Leaf,
+    Introduce(String),
+    Forget(String),
+    Join,
+    Root
+}
+
+impl TreeDecomposition {
+    /// Writes nice tree decompositions of `graph` (e.g. the trees of a forest) to a file, see [`Self::to_json`].
+    pub fn save(tds: &[Self], graph: &Graph, path: &str) -> Result<()> {
+        std::fs::write(path, Self::to_json(tds, graph)).map_err(|source| TdIsError::Output { path: path.to_string(), source })
+    }
+
+    /// Reads nice tree decompositions of `graph` written by [`Self::save`], see [`Self::from_json`].
+    pub fn load(path: &str, graph: &Graph) -> Result<Vec<Self>> {
+        eprintln!("Loading nice treedecomposition from {}", path);
+        Self::from_json(&read_input(path)?, path, graph)
+    }
+
+    /// Writes nice tree decompositions of `graph` as JSON document with their nodes, node types, bags, links and leaves.
+    pub fn to_json(tds: &[Self], graph: &Graph) -> String {
+        let name = |v: &usize| graph.get_vertex_name(*v).clone();
+        let trees = tds.iter().map(|td| TreeEntry {
+            nodes: (0..td.len()).map(|idx| {
+                let node = td.get_node(idx);
+                NodeEntry {
+                    node_type: match node.node_type() {
+                        NodeType::Leaf => NodeTypeEntry::Leaf,
+                        NodeType::Introduce(v) => NodeTypeEntry::Introduce(name(v)),
+                        NodeType::Forget(v) => NodeTypeEntry::Forget(name(v)),
+                        NodeType::Join => NodeTypeEntry::Join,
+                        NodeType::Root => NodeTypeEntry::Root
+                    },
+                    bag: node.bag().vertices().iter().map(name).collect(),
+                    next: if idx == 0 { None } else { Some(node.next()) },
+                    prev: node.prev().clone()
+                }
+            }).collect(),
+            leaves: td.leaves().clone()
+        }).collect();
+        serde_json::to_string(&NiceTdFile { trees }).expect("tree decomposition is serializable")
+    }
+
+    /// Parses nice tree decompositions of `graph` from a JSON document written by [`Self::to_json`].
+    /// Checks that every tree is a nice tree decomposition rooted at node 0 and that together they form a tree decomposition of `graph`.
+    pub fn from_json(content: &str, path: &str, graph: &Graph) -> Result<Vec<Self>> {
+        let file: NiceTdFile = serde_json::from_str(content).map_err(|e| TdIsError::Json { path: path.to_string(), message: e.to_string() })?;
+        let vertex = |name: &String, tree: usize, idx: usize| {
+            graph.get_vertex_idx(name).copied()
+                .ok_or_else(|| TdIsError::InvalidTd(format!("node {} of tree {} contains unknown vertex '{}'", idx, tree, name)))
+        };
+
+        let mut tds = Vec::with_capacity(file.trees.len());
+        for (tree, entry) in file.trees.into_iter().enumerate() {
+            let mut nodes = Vec::with_capacity(entry.nodes.len());
+            for (idx, node_entry) in entry.nodes.into_iter().enumerate() {
+                let node_type = match &node_entry.node_type {
+                    NodeTypeEntry::Leaf => NodeType::Leaf,
+                    NodeTypeEntry::Introduce(v) => NodeType::Introduce(vertex(v, tree, idx)?),
+                    NodeTypeEntry::Forget(v) => NodeType::Forget(vertex(v, tree, idx)?),
+                    NodeTypeEntry::Join => NodeType::Join,
+                    NodeTypeEntry::Root => NodeType::Root
+                };
+                let mut bag = node_entry.bag.iter().map(|v| vertex(v, tree, idx)).collect::<Result<Vec<usize>>>()?;
+                bag.sort();
+                bag.dedup();
+                if bag.len() > MAX_BAG_SIZE {
+                    return Err(TdIsError::BagTooLarge { bag: format!("{} of tree {}", idx, tree), size: bag.len() });
+                }
+                let mut node = Node::new(Bag::new(bag), node_type, node_entry.next.unwrap_or(usize::MAX));
+                for prev in node_entry.prev {
+                    node.add_to_prev(prev);
+                }
+                nodes.push(node);
+            }
+
+            if let Some(msg) = check_nice(&nodes, &entry.leaves, graph) {
+                return Err(TdIsError::InvalidTd(format!("tree {}: {}", tree, msg)));
+            }
+            tds.push(Self::from_parts(nodes, entry.leaves));
+        }
+
+        // The nodes of all trees have to form a tree decomposition of the graph
+        let mut bags = Vec::new();
+        let mut edges = Vec::new();
+        for td in tds.iter() {
+            let offset = bags.len();
+            for idx in 0..td.len() {
+                let node = td.get_node(idx);
+                bags.push(node.bag().vertices().clone());
+                edges.push(node.prev().iter().map(|prev| prev + offset).collect());
+            }
+        }
+        let violations = InputTreeDecomposition::from_bags(bags, edges, graph)?.validate(graph);
+        if !violations.is_empty() {
+            return Err(TdIsError::InvalidTd(violations.iter().join("; ")));
+        }
+
+        Ok(tds)
+    }
+}
+
+/// Checks that the nodes form a nice tree decomposition below the dummy root node 0 and returns the first problem found.
+fn check_nice(nodes: &[Node], leaves: &[usize], graph: &Graph) -> Option<String> {
+    match nodes.first() {
+        Some(root) if *root.node_type() == NodeType::Root && root.bag().is_empty() && *root.prev() == [1] => {},
+        _ => return Some("node 0 is not a root node with an empty bag and node 1 as only child".to_string())
+    }
+
+    for (idx, node) in nodes.iter().enumerate() {
+        if let Some(prev) = node.prev().iter().find(|prev| **prev == 0 || **prev >= nodes.len() || nodes[**prev].next() != idx) {
+            return Some(format!("node {} has child {}, which does not link back to it", idx, prev));
+        }
+        let prev_bag = |i: usize| nodes[node.prev()[i]].bag().vertices();
+        let consistent = match node.node_type() {
+            NodeType::Root => idx == 0,
+            NodeType::Leaf => node.prev().is_empty(),
+            NodeType::Introduce(v) => node.prev().len() == 1 && node.bag().vertices().iter().filter(|u| *u != v).eq(prev_bag(0).iter()) && node.bag().position(*v).is_some(),
+            NodeType::Forget(v) => node.prev().len() == 1 && prev_bag(0).iter().filter(|u| *u != v).eq(node.bag().vertices().iter()) && prev_bag(0).contains(v),
+            NodeType::Join => node.prev().len() == 2 && node.prev()[0] != node.prev()[1] && prev_bag(0) == node.bag().vertices() && prev_bag(1) == node.bag().vertices()
+        };
+        if !consistent {
+            return Some(format!("node {} of type {} does not match its bag or children", idx, node.node_type().label(graph)));
+        }
+    }
+
+    // Every node has to be reachable from the root, which rules out cycles
+    let mut reached = 0;
+    let mut stack = vec![0];
+    while let Some(idx) = stack.pop() {
+        reached += 1;
+        stack.extend(nodes[idx].prev());
+    }
+    if reached != nodes.len() {
+        return Some("not all nodes are connected to the root".to_string());
+    }
+
+    let mut expected_leaves = (0..nodes.len()).filter(|idx| nodes[*idx].prev().is_empty()).collect::<Vec<usize>>();
+    let mut leaves = leaves.to_vec();
+    expected_leaves.sort();
+    leaves.sort();
+    if leaves != expected_leaves {
+        return Some("leaves do not match the nodes without children".to_string());
+    }
+    None
+}
@@ -1,9 +1,9 @@
 use std::cmp;
 
-use bit_vec::BitVec;
+use rustc_hash::FxHashSet;
 
-use super::{Bag, Graph, TreeDecomposition};
-use rustc_hash::FxHashMap;
+use super::{Bag, DpTable, Graph, Mask, TreeDecomposition};
+use crate::SolverOptions;
 
 #[derive(Debug)]
 pub struct Node {
@@ -11,7 +11,8 @@ pub struct Node {
     node_type: NodeType,
     next: usize,
     prev: Vec<usize>,
-    max_is: FxHashMap<Vec<usize>, usize>
+    max_is: DpTable,
+    decisions: Option<FxHashSet<Mask>>  // Entries of a forget node for which the forgotten vertex was taken, if recorded
 }
 
 impl Node {
@@ -21,10 +22,19 @@ impl Node {
             node_type,
             next,
             prev: Vec::new(),
-            max_is: FxHashMap::default()
+            max_is: DpTable::default(),
+            decisions: None
         }
     }
 
+    pub fn bag(&self) -> &Bag {
+        &self.bag
+    }
+
+    pub fn node_type(&self) -> &NodeType {
+        &self.node_type
+    }
+
     pub fn next(&self) -> usize {
         self.next
     }
@@ -37,12 +47,28 @@ impl Node {
         self.prev.push(prev)
     }
     
-    pub fn max_is(&self) -> &FxHashMap<Vec<usize>, usize> {
+    pub fn max_is(&self) -> &DpTable {
         &self.max_is
     }
 
-    pub fn get_weight(&self, set: &Vec<usize>) -> usize {
-        *self.max_is.get(set).unwrap()
+    pub fn get_weight(&self, mask: Mask) -> Option<usize> {
+        self.max_is.get(mask)
+    }
+
+    /// Drops the table, keeping recorded decisions.
+    pub fn clear_table(&mut self) {
+        self.max_is = DpTable::default();
+    }
+
+    /// Drops the table and the recorded decisions, e.g. those of an earlier solve.
+    pub fn reset(&mut self) {
+        self.max_is = DpTable::default();
+        self.decisions = None;
+    }
+
+    /// Whether [`Node::prev_entries`] needs the table of the previous node.
+    pub fn needs_prev_table(&self) -> bool {
+        matches!(self.node_type, NodeType::Forget(_)) && self.decisions.is_none()
     }
 
     pub fn can_traverse(&self, td: &TreeDecomposition) -> bool {
@@ -55,138 +81,93 @@ impl Node {
         }
     }
 
-    pub fn update_entries(&mut self, graph: &Graph, td: &TreeDecomposition) {
+    /// Computes the table entries for all independent subsets of the bag from the tables of the previous nodes.
+    pub fn update_entries(&mut self, graph: &Graph, td: &TreeDecomposition, options: &SolverOptions) {
+        let subsets = self.bag.independent_subsets(graph);
+        self.max_is = DpTable::new(self.bag.len(), subsets.len());
         match self.node_type {
             NodeType::Leaf => {
-                for set in self.bag.get_powerset() {
-                    if graph.is_is(&set) {
-                        let weight = graph.get_weight_of_set(&set);
-                        self.max_is.insert(set, weight);
-                    } else {
-                        self.max_is.insert(set, 0);
-                    }
+                for mask in subsets {
+                    self.max_is.insert(mask, self.bag.weight_of(mask, graph));
                 }
             },
             NodeType::Forget(v) => {
-                for mut set in self.bag.get_powerset() {
-                    let prev_node = td.get_node(self.prev[0]);
-                    let prev_weight = prev_node.get_weight(&set);
-                    set.push(v);    // Add forgotten vertex to set for weight calculation
-                    set.sort();
-                    let weight = cmp::max(prev_weight, prev_node.get_weight(&set));
-                    set.remove(set.iter().position(|x| *x == v).unwrap());    // Remove forgotten vertex again
-                    
-                    self.max_is.insert(set, weight);
+                let prev_node = td.get_node(self.prev[0]);
+                let pos = prev_node.bag().position(v).unwrap();
+                let mut decisions = FxHashSet::default();
+                for mask in subsets {
+                    let prev_mask = insert_bit(mask, pos);
+                    let without_v = prev_node.get_weight(prev_mask).unwrap();
+                    let with_v = prev_node.get_weight(prev_mask | (1 << pos));
+                    if options.record_decisions && with_v > Some(without_v) {
+                        decisions.insert(mask);
+                    }
+                    self.max_is.insert(mask, cmp::max(with_v, Some(without_v)).unwrap());
                 }
+                self.decisions = options.record_decisions.then_some(decisions);
             },
             NodeType::Introduce(v) => {
-                for mut set in self.bag.get_powerset() {
-                    let prev_node = td.get_node(self.prev[0]);
-                    let weight = if set.contains(&v) {
-                        set.remove(set.iter().position(|x| *x == v).unwrap());    // Remove introduced vertex
-                        let w = if prev_node.get_weight(&set) == 0 || graph.is_neighbor(v, &set) {
-                            0   // Not an IS
-                        } else {
-                            prev_node.get_weight(&set) + graph.get_weight(v)
-                        };
-                        set.push(v);
-                        set.sort();
-                        w
+                let prev_node = td.get_node(self.prev[0]);
+                let pos = self.bag.position(v).unwrap();
+                for mask in subsets {
+                    let prev_weight = prev_node.get_weight(remove_bit(mask, pos)).unwrap();
+                    let weight = if mask & (1 << pos) == 0 {
+                        prev_weight
                     } else {
-                        prev_node.get_weight(&set)
+                        prev_weight + graph.get_weight(v)
                     };
-                    self.max_is.insert(set, weight);
+                    self.max_is.insert(mask, weight);
                 }
             },
             NodeType::Join => {
-                for set in self.bag.get_powerset() {
-                    let left_weight = td.get_node(self.prev[0]).get_weight(&set);
-                    let right_weight = td.get_node(self.prev[1]).get_weight(&set);
-                    let weight = if left_weight == 0 {
-                        right_weight
-                    } else if right_weight == 0 {
-                        left_weight
-                    } else {
-                        left_weight + right_weight - graph.get_weight_of_set(&set)
-                    };
-                    self.max_is.insert(set, weight);
+                let left_node = td.get_node(self.prev[0]);
+                let right_node = td.get_node(self.prev[1]);
+                for mask in subsets {
+                    // The weight of the set itself is counted in both children
+                    let weight = left_node.get_weight(mask).unwrap() + right_node.get_weight(mask).unwrap() - self.bag.weight_of(mask, graph);
+                    self.max_is.insert(mask, weight);
                 }
             },
             NodeType::Root => {}
         };
     }
 
-    // (Set, prev idx)
-    pub fn get_solution(&self, solution: &mut Vec<usize>, rejected: &mut BitVec, curr_obj: usize, graph: &Graph, td: &TreeDecomposition) {
+    /// The previous nodes together with the entries the entry `mask` of this node was computed from.
+    pub fn prev_entries(&self, mask: Mask, td: &TreeDecomposition) -> Vec<(usize, Mask)> {
         match self.node_type {
-            NodeType::Leaf => {
-                for set in self.bag.get_powerset() {
-                    if set.iter().any(|i| rejected[*i]) || self.get_weight(&set) != curr_obj { continue; }   // Skip rejected sets or ones with wrong weight
-
-                    self.bag.vertices().iter().for_each(|v| if !set.contains(v) { rejected.set(*v, true) });
-                    set.iter().for_each(|v| if !solution.contains(v) { solution.push(*v)});
-                    break;
-                }
-            },
+            NodeType::Leaf | NodeType::Root => Vec::new(),
             NodeType::Forget(v) => {
-                for set in self.bag.get_powerset() {
-                    if set.iter().any(|i| rejected[*i]) || self.get_weight(&set) != curr_obj { continue; }   // Skip rejected sets or ones with wrong weight
-
-                    let prev_node = td.get_node(self.prev[0]);
-                    if prev_node.get_weight(&set) == curr_obj {
-                        rejected.set(v, true);  // Same weight with forget -> cannot be part of solution
-                        td.get_node(self.prev[0]).get_solution(solution, rejected, curr_obj, graph, td);
-                    } else {
-                        if !solution.contains(&v) { solution.push(v); }
-                        td.get_node(self.prev[0]).get_solution(solution, rejected, curr_obj, graph, td);
-                    }
-                    break;
-                }
+                let prev_node = td.get_node(self.prev[0]);
+                let pos = prev_node.bag().position(v).unwrap();
+                let prev_mask = insert_bit(mask, pos);
+                let taken = match &self.decisions {
+                    Some(decisions) => decisions.contains(&mask),
+                    None => prev_node.get_weight(prev_mask | (1 << pos)) > prev_node.get_weight(prev_mask)
+                };
+                vec![(self.prev[0], if taken { prev_mask | (1 << pos) } else { prev_mask })]
             },
             NodeType::Introduce(v) => {
-                for set in self.bag.get_powerset() {
-                    if set.iter().any(|i| rejected[*i]) || self.get_weight(&set) != curr_obj { continue; }   // Skip rejected sets or ones with wrong weight
-
-                    if set.contains(&v) {
-                        if !solution.contains(&v) { solution.push(v); }
-                        self.bag.vertices().iter().for_each(|v| if !set.contains(v) { rejected.set(*v, true) }); 
-
-                        td.get_node(self.prev[0]).get_solution(solution, rejected, curr_obj - graph.get_weight(v), graph, td);
-                    } else {
-                        rejected.set(v, true);
-                        td.get_node(self.prev[0]).get_solution(solution, rejected, curr_obj, graph, td);
-                    }
-                    break;
-                }
+                vec![(self.prev[0], remove_bit(mask, self.bag.position(v).unwrap()))]
             },
             NodeType::Join => {
-                for set in self.bag.get_powerset() {
-                    if set.iter().any(|i| rejected[*i]) || self.get_weight(&set) != curr_obj { continue; }   // Skip rejected sets or ones with wrong weight
-
-                    let left_weight = td.get_node(self.prev[0]).get_weight(&set);
-                    let right_weight = td.get_node(self.prev[1]).get_weight(&set);
-                    if left_weight == curr_obj {
-                        set.iter().for_each(|v| if !solution.contains(v) { solution.push(*v); });                        
-                        self.bag.vertices().iter().for_each(|v| if !set.contains(v) { rejected.set(*v, true) });
-                        td.get_node(self.prev[0]).get_solution(solution, rejected, curr_obj, graph, td);
-                    } else if right_weight == curr_obj {
-                        set.iter().for_each(|v| if !solution.contains(v) { solution.push(*v); });      
-                        self.bag.vertices().iter().for_each(|v| if !set.contains(v) { rejected.set(*v, true) });                        
-                        td.get_node(self.prev[1]).get_solution(solution, rejected, curr_obj, graph, td);
-                    } else if left_weight + right_weight - graph.get_weight_of_set(&set) == curr_obj{
-                        set.iter().for_each(|v| if !solution.contains(v) { solution.push(*v); });
-                        self.bag.vertices().iter().for_each(|v| if !set.contains(v) { rejected.set(*v, true) });
-                        td.get_node(self.prev[0]).get_solution(solution, rejected, left_weight, graph, td);
-                        td.get_node(self.prev[1]).get_solution(solution, rejected, right_weight, graph, td);
-                    };
-                    break;
-                }
-            },
-            NodeType::Root => {}
+                vec![(self.prev[0], mask), (self.prev[1], mask)]
+            }
         }
     }
 }
 
+/// Inserts a zero bit at position `pos`, shifting the higher bits up.
+fn insert_bit(mask: Mask, pos: usize) -> Mask {
+    let low = mask & ((1 << pos) - 1);
+    ((mask ^ low) << 1) | low
+}
+
+/// Removes the bit at position `pos`, shifting the higher bits down.
+fn remove_bit(mask: Mask, pos: usize) -> Mask {
+    let low = mask & ((1 << pos) - 1);
+    ((mask >> pos >> 1) << pos) | low   // Two shifts, as `pos + 1` may be the width of the mask
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum NodeType {
     Leaf,
@@ -194,4 +175,17 @@ pub enum NodeType {
     Forget(usize),
     Join,
     Root
-} 
\ No newline at end of file
+}
+
+impl NodeType {
+    /// Name of the node type with the introduced or forgotten vertex given by its name.
+    pub fn label(&self, graph: &Graph) -> String {
+        match self {
+            NodeType::Leaf => "Leaf".to_string(),
+            NodeType::Introduce(v) => format!("Introduce({})", graph.get_vertex_name(*v)),
+            NodeType::Forget(v) => format!("Forget({})", graph.get_vertex_name(*v)),
+            NodeType::Join => "Join".to_string(),
+            NodeType::Root => "Root".to_string()
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+/// A reason why a set of bags is not a tree decomposition of a graph, see [`super::InputTreeDecomposition::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TdViolation {
+    /// The vertex is not contained in any bag.
+    UncoveredVertex(String),
+    /// No bag contains both endpoints of the edge.
+    UncoveredEdge(String, String),
+    /// The bags containing the vertex do not form a connected subtree.
+    DisconnectedVertex(String, Vec<String>),
+    /// The edge between the two bags closes a cycle.
+    Cycle(String, String)
+}
+
+impl fmt::Display for TdViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TdViolation::UncoveredVertex(v) => write!(f, "vertex {} is not contained in any bag", v),
+            TdViolation::UncoveredEdge(u, v) => write!(f, "edge {}-{} is not contained in any bag", u, v),
+            TdViolation::DisconnectedVertex(v, bags) => write!(f, "bags containing vertex {} ({}) are not connected", v, bags.iter().join(", ")),
+            TdViolation::Cycle(a, b) => write!(f, "edge {}-{} closes a cycle", a, b)
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use std::cell::{Ref, RefCell, RefMut};
+use std::fmt::Write;
 
 use super::*;
+use crate::error::{Result, TdIsError};
 use bit_vec::BitVec;
 use itertools::Itertools;
 
@@ -12,34 +14,54 @@ pub struct TreeDecomposition {
 }
 
 impl TreeDecomposition {
-    pub fn new(td_path: &str, graph: &Graph) -> Vec<Self> {
-        let input_td = InputTreeDecomposition::new(td_path, &graph);
+    /// Parses a tree decomposition of `graph` from a CSV file with `bag,,v1;v2;...` and `bag,bag` lines
+    /// and converts every tree of it into a nice tree decomposition.
+    pub fn new(td_path: &str, graph: &Graph) -> Result<Vec<Self>> {
+        let input_td = InputTreeDecomposition::new(td_path, graph)?;
+        Self::from_input(&input_td, graph)
+    }
+
+    /// Creates a nice tree decomposition from bags given as lists of graph vertex indices and an adjacency list over the bags.
+    /// See [`InputTreeDecomposition::from_bags`].
+    pub fn from_bags(bags: Vec<Vec<usize>>, edges: Vec<Vec<usize>>, graph: &Graph) -> Result<Vec<Self>> {
+        Self::from_input(&InputTreeDecomposition::from_bags(bags, edges, graph)?, graph)
+    }
+
+    /// Validates `input_td` against `graph` and converts every tree of it into a nice tree decomposition.
+    /// Bags may have at most 128 vertices.
+    /// Bags without any neighbors form a tree on their own. Each tree is rooted at the bag for which the
+    /// estimated cost of the dynamic program is lowest, see [`InputTreeDecomposition::rooting_costs`].
+    pub fn from_input(input_td: &InputTreeDecomposition, graph: &Graph) -> Result<Vec<Self>> {
+        let violations = input_td.validate(graph);
+        if !violations.is_empty() {
+            return Err(TdIsError::InvalidTd(violations.iter().join("; ")));
+        }
+        if let Some(i) = (0..input_td.len()).find(|i| input_td.get_bag(*i).len() > MAX_BAG_SIZE) {
+            return Err(TdIsError::BagTooLarge { bag: input_td.get_bag_name(i).clone(), size: input_td.get_bag(i).len() });
+        }
+
         let mut tree_decompositions: Vec<TreeDecomposition> = Vec::new();
         let mut bag_treated = BitVec::from_elem(input_td.len(), false);
-        for (i, v) in input_td.edges().iter().enumerate() {
-            if v.is_empty() {
-                bag_treated.set(i, true);
-            }
-        }
+        let costs = input_td.rooting_costs(graph);
+        let mut bag_reached = BitVec::from_elem(input_td.len(), false);
 
-        while !bag_treated.all() {
-            // Create a new TD with the first untreated node as a root
-            let root_idx = match input_td.edges().iter().enumerate().find(|(i, _)| !bag_treated[*i]) {
-                Some((i, _)) => i,
-                None => panic!("No leaf left => cycle => input not a TD!")
-            };
+        while let Some(first) = (0..input_td.len()).find(|i| !bag_treated[*i]) {
+            // Create a new TD for the tree of the first untreated bag, rooted at its cheapest bag
+            let root_idx = Self::tree_bags(input_td, first, &mut bag_reached).into_iter()
+                .min_by(|a, b| costs[*a].total_cmp(&costs[*b]).then(a.cmp(b)))
+                .unwrap_or(first);
 
-            tree_decompositions.push(Self::from_root(&input_td, &mut bag_treated, root_idx));
+            tree_decompositions.push(Self::from_root(input_td, &mut bag_treated, root_idx));
         }
         
-        tree_decompositions
+        Ok(tree_decompositions)
     }
 
-    pub fn get_node(&self, idx: usize) -> Ref<Node> {
+    pub fn get_node(&self, idx: usize) -> Ref<'_, Node> {
         self.nodes[idx].borrow()
     }
 
-    pub fn get_node_mut(&self, idx: usize) -> RefMut<Node> {
+    pub fn get_node_mut(&self, idx: usize) -> RefMut<'_, Node> {
         self.nodes[idx].borrow_mut()
     }
 
@@ -47,16 +69,107 @@ impl TreeDecomposition {
         &self.leaves
     }
 
+    /// Leaves of the subtree below (and including) the node with index `idx`.
+    pub fn leaves_below(&self, idx: usize) -> Vec<usize> {
+        let mut leaves = Vec::new();
+        let mut stack = vec![idx];
+        while let Some(i) = stack.pop() {
+            let node = self.get_node(i);
+            if node.prev().is_empty() {
+                leaves.push(i);
+            }
+            stack.extend(node.prev());
+        }
+        leaves
+    }
+
+    pub(super) fn from_parts(nodes: Vec<Node>, leaves: Vec<usize>) -> Self {
+        TreeDecomposition {
+            nodes: nodes.into_iter().map(RefCell::new).collect_vec(),
+            leaves
+        }
+    }
+
+    /// Number of nodes, including the dummy root node 0.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Size of the largest bag minus one.
+    pub fn width(&self) -> usize {
+        self.nodes.iter().map(|node| node.borrow().bag().len()).max().unwrap_or(1).saturating_sub(1)
+    }
+
+    /// Writes the nice tree decomposition as Graphviz DOT graph. Every node is labelled with its type and the names
+    /// of the vertices in its bag and, if `with_tables` is set, the size of its DP table and best weight in it.
+    pub fn to_dot(&self, graph: &Graph, with_tables: bool) -> String {
+        Self::forest_to_dot(std::slice::from_ref(self), graph, with_tables)
+    }
+
+    /// Writes several nice tree decompositions (e.g. the trees of a forest) as one DOT graph with a cluster per tree.
+    /// See [`Self::to_dot`].
+    pub fn forest_to_dot(tds: &[Self], graph: &Graph, with_tables: bool) -> String {
+        let mut dot = String::from("digraph td {\n    node [shape=box];\n");
+        let indent = if tds.len() > 1 { "        " } else { "    " };
+        for (i, td) in tds.iter().enumerate() {
+            if tds.len() > 1 {
+                writeln!(dot, "    subgraph cluster_{} {{\n{}label=\"tree {}\";", i, indent, i + 1).unwrap();
+            }
+            for idx in 0..td.len() {
+                let node = td.get_node(idx);
+                let bag = node.bag().vertices().iter().map(|v| escape(graph.get_vertex_name(*v))).join(", ");
+                let mut label = format!("{}\\n{{{}}}", escape(&node.node_type().label(graph)), bag);
+                if with_tables && !node.max_is().is_empty() {
+                    let best = node.max_is().iter().map(|(_, weight)| weight).max().unwrap_or(0);
+                    write!(label, "\\ntable: {} entries, best: {}", node.max_is().len(), best).unwrap();
+                }
+                writeln!(dot, "{}t{}_{} [label=\"{}\"];", indent, i, idx, label).unwrap();
+                for prev in node.prev() {
+                    writeln!(dot, "{}t{}_{} -> t{}_{};", indent, i, idx, i, prev).unwrap();
+                }
+            }
+            if tds.len() > 1 {
+                dot.push_str("    }\n");
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Estimated cost of the dynamic program: the sum of the table sizes, i.e. the numbers of independent subsets
+    /// of the bags, over all nodes but the dummy root.
+    pub fn estimated_cost(&self, graph: &Graph) -> f64 {
+        self.nodes.iter().skip(1).map(|node| node.borrow().bag().count_independent_subsets(graph)).sum()
+    }
+
+    /// Bags of the tree of `input_td` containing the bag `start`, which are marked in `reached`.
+    fn tree_bags(input_td: &InputTreeDecomposition, start: usize, reached: &mut BitVec) -> Vec<usize> {
+        let mut bags = vec![start];
+        reached.set(start, true);
+        let mut i = 0;
+        while i < bags.len() {
+            for neighbor in input_td.edges()[bags[i]].iter() {
+                if !reached[*neighbor] {
+                    reached.set(*neighbor, true);
+                    bags.push(*neighbor);
+                }
+            }
+            i += 1;
+        }
+        bags
+    }
+
     fn from_root(input_td: &InputTreeDecomposition, bag_treated: &mut BitVec, root_idx: usize) -> Self {
         let mut nodes = Vec::new();
         let mut leaves = Vec::new();
         nodes.push(Node::new(Bag::new_empty(), NodeType::Root, usize::MAX)); // Empty dummy root node, so the rest works recursively from this
         Self::create_nodes(input_td, bag_treated, &mut nodes, &mut leaves, root_idx, 0, &input_td.edges()[root_idx]);
 
-        TreeDecomposition {
-            nodes: nodes.into_iter().map(|node| RefCell::new(node)).collect_vec(),
-            leaves
-        }
+        Self::from_parts(nodes, leaves)
     }
 
     fn create_nodes(input_td: &InputTreeDecomposition, bag_treated: &mut BitVec, new_nodes: &mut Vec<Node>, leaves: &mut Vec<usize>, curr_bag: usize, last_node: usize, neighbors: &[usize]) {
@@ -84,8 +197,8 @@ impl TreeDecomposition {
         // Exactly one neighbor, use introduce, forget, etc nodes.
         let neighbor_idx = neighbors[0];
         let neighbor = input_td.get_bag(neighbor_idx);
-        let to_introduce = bag.vertices().iter().filter(|v| !neighbor.vertices().contains(*v)).map(|x| *x).collect::<Vec<usize>>();
-        let to_forget = neighbor.vertices().iter().filter(|v| !bag.vertices().contains(*v)).map(|x| *x).collect::<Vec<usize>>();
+        let to_introduce = bag.vertices().iter().filter(|v| !neighbor.vertices().contains(*v)).copied().collect::<Vec<usize>>();
+        let to_forget = neighbor.vertices().iter().filter(|v| !bag.vertices().contains(*v)).copied().collect::<Vec<usize>>();
 
         // Add introduce nodes
         let mut working_bag = bag.vertices().clone();
@@ -109,7 +222,12 @@ impl TreeDecomposition {
 
         bag_treated.set(curr_bag, true);
 
-        let neighbors = input_td.edges()[neighbor_idx].iter().filter(|v| !bag_treated[**v]).map(|x| *x).collect::<Vec<usize>>();
+        let neighbors = input_td.edges()[neighbor_idx].iter().filter(|v| !bag_treated[**v]).copied().collect::<Vec<usize>>();
         Self::create_nodes(input_td, bag_treated, new_nodes, leaves, neighbor_idx, prev_node_idx, &neighbors);
     }
-}
\ No newline at end of file
+}
+
+/// Escapes a vertex name for use in a quoted DOT label.
+fn escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
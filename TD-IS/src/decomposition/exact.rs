@@ -0,0 +1,220 @@
+//! Exact treewidth by dynamic programming over vertex subsets.
+//!
+//! For a set `S` of already eliminated vertices, eliminating `v` next creates a bag of `v` and the vertices outside
+//! of `S` reachable from `v` through `S`. The graph has treewidth at most `k` if the set of all vertices can be reached
+//! from the empty set by eliminating vertices one by one with bags of at most `k + 1` vertices. Only the sets reachable
+//! this way are enumerated, for `k` increasing from a lower bound up to the width of the best heuristic ordering.
+//! Each connected component is handled separately; components with more than 128 vertices keep the heuristic ordering.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use rustc_hash::FxHashMap;
+
+use super::{best_heuristic_ordering, from_ordering};
+use crate::{Graph, InputTreeDecomposition, Result};
+
+/// Default for the largest number of subsets the search stores for one width, taking up to about 100 MB.
+/// Every set costs up to about 100 bytes, in the map of reached sets or on the stack of sets to expand.
+pub const DEFAULT_MAX_SETS: usize = 1 << 20;
+
+/// A tree decomposition computed by [`decompose_exact`].
+#[derive(Debug)]
+pub struct ExactDecomposition {
+    pub td: InputTreeDecomposition,
+    /// Why the heuristic ordering was kept, for every connected component whose search did not finish.
+    pub fallbacks: Vec<Fallback>
+}
+
+impl ExactDecomposition {
+    /// Whether the width of `td` is the treewidth of the graph, i.e. the search finished for every component.
+    pub fn is_optimal(&self) -> bool {
+        self.fallbacks.is_empty()
+    }
+}
+
+/// Reason why a connected component keeps the best heuristic ordering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fallback {
+    /// The component has the given number of vertices, more than the 128 the search supports.
+    TooLarge(usize),
+    /// The timeout passed during the search.
+    Timeout,
+    /// The search stored more than the given maximum number of sets for one width.
+    TooManySets
+}
+
+impl fmt::Display for Fallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fallback::TooLarge(size) => write!(f, "component of {} vertices too large for exact search", size),
+            Fallback::Timeout => write!(f, "exact search timed out"),
+            Fallback::TooManySets => write!(f, "exact search reached too many vertex sets")
+        }
+    }
+}
+
+/// Computes a tree decomposition of minimum width, falling back to the best heuristic ordering for the components
+/// that are too large or whose search did not finish within `timeout` and with at most `max_sets` sets stored,
+/// see [`DEFAULT_MAX_SETS`].
+pub fn decompose_exact(graph: &Graph, timeout: Duration, max_sets: usize) -> Result<ExactDecomposition> {
+    let deadline = Instant::now() + timeout;
+    let heuristic_ordering = best_heuristic_ordering(graph)?;
+    let components = components(graph);
+    let mut component_orderings = vec![Vec::new(); components.len()];
+    let mut component_of = vec![0; graph.size()];
+    for (i, component) in components.iter().enumerate() {
+        for v in component.iter() {
+            component_of[*v] = i;
+        }
+    }
+    for v in heuristic_ordering {
+        component_orderings[component_of[v]].push(v);
+    }
+
+    let mut ordering = Vec::with_capacity(graph.size());
+    let mut fallbacks = Vec::new();
+    for (component, component_ordering) in components.iter().zip(component_orderings) {
+        match exact_ordering(graph, component, &component_ordering, deadline, max_sets) {
+            Ok(exact) => ordering.extend(exact),
+            Err(fallback) => {
+                fallbacks.push(fallback);
+                ordering.extend(component_ordering);
+            }
+        }
+    }
+
+    Ok(ExactDecomposition { td: from_ordering(graph, &ordering)?, fallbacks })
+}
+
+/// Vertices of the connected components of `graph`, each sorted.
+fn components(graph: &Graph) -> Vec<Vec<usize>> {
+    let mut seen = vec![false; graph.size()];
+    let mut components = Vec::new();
+    for start in 0..graph.size() {
+        if seen[start] { continue; }
+
+        seen[start] = true;
+        let mut component = Vec::new();
+        let mut stack = vec![start];
+        while let Some(v) = stack.pop() {
+            component.push(v);
+            for u in graph.neighbors(v) {
+                if !seen[*u] {
+                    seen[*u] = true;
+                    stack.push(*u);
+                }
+            }
+        }
+        component.sort();
+        components.push(component);
+    }
+    components
+}
+
+/// Computes an optimal elimination ordering of the (connected) `component`, given a heuristic one as upper bound.
+/// Returns the reason if the component is too large or the search does not finish.
+fn exact_ordering(graph: &Graph, component: &[usize], heuristic_ordering: &[usize], deadline: Instant, max_sets: usize) -> std::result::Result<Vec<usize>, Fallback> {
+    if component.len() > 128 { return Err(Fallback::TooLarge(component.len())); }
+
+    let local = |v: usize| component.binary_search(&v).unwrap();
+    let adjacency = component.iter()
+        .map(|v| graph.neighbors(*v).iter().filter(|u| *u != v).fold(0u128, |mask, u| mask | (1 << local(*u))))
+        .collect::<Vec<u128>>();
+
+    let mut upper_bound = 0;
+    let mut eliminated = 0u128;
+    for v in heuristic_ordering.iter().map(|v| local(*v)) {
+        upper_bound = upper_bound.max(bag_size(&adjacency, eliminated, v));
+        eliminated |= 1 << v;
+    }
+
+    for k in lower_bound(&adjacency)..upper_bound {
+        match feasible_ordering(&adjacency, k, deadline, max_sets) {
+            Search::Found(ordering) => return Ok(ordering.into_iter().map(|v| component[v]).collect()),
+            Search::Infeasible => continue,
+            Search::Aborted(fallback) => return Err(fallback)
+        }
+    }
+    Ok(heuristic_ordering.to_vec())    // No smaller width possible
+}
+
+/// Number of vertices besides `v` in the bag created by eliminating `v` after the vertices in `eliminated`,
+/// i.e. the vertices outside of `eliminated` reachable from `v` through `eliminated`.
+fn bag_size(adjacency: &[u128], eliminated: u128, v: usize) -> u32 {
+    let mut reached = 0u128;
+    let mut neighbors = adjacency[v];
+    let mut todo = adjacency[v] & eliminated;
+    while todo != 0 {
+        let u = todo.trailing_zeros() as usize;
+        todo &= todo - 1;
+        reached |= 1 << u;
+        neighbors |= adjacency[u];
+        todo |= adjacency[u] & eliminated & !reached;
+    }
+    (neighbors & !eliminated & !(1 << v)).count_ones()
+}
+
+/// Maximum over all subgraphs of their minimum degree, found by repeatedly removing a vertex of minimum degree.
+fn lower_bound(adjacency: &[u128]) -> u32 {
+    let mut remaining = if adjacency.len() == 128 { u128::MAX } else { (1 << adjacency.len()) - 1 };
+    let mut bound = 0;
+    while remaining != 0 {
+        let (v, degree) = (0..adjacency.len())
+            .filter(|v| remaining & (1 << v) != 0)
+            .map(|v| (v, (adjacency[v] & remaining).count_ones()))
+            .min_by_key(|(_, degree)| *degree)
+            .unwrap();
+        bound = bound.max(degree);
+        remaining &= !(1 << v);
+    }
+    bound
+}
+
+/// Outcome of the search for an elimination ordering of bounded width.
+enum Search {
+    Found(Vec<usize>),
+    Infeasible,
+    /// The deadline passed or too many sets were reached.
+    Aborted(Fallback)
+}
+
+/// Searches an elimination ordering in which every bag has at most `k + 1` vertices.
+fn feasible_ordering(adjacency: &[u128], k: u32, deadline: Instant, max_sets: usize) -> Search {
+    let all = if adjacency.len() == 128 { u128::MAX } else { (1 << adjacency.len()) - 1 };
+    let mut last_eliminated: FxHashMap<u128, usize> = FxHashMap::default();    // Vertex eliminated last to reach a set
+    let mut stack = vec![0u128];
+    let mut steps = 0usize;
+    while let Some(eliminated) = stack.pop() {
+        steps += 1;
+        if steps.is_multiple_of(1024) && Instant::now() >= deadline {
+            return Search::Aborted(Fallback::Timeout);
+        }
+        if last_eliminated.len() + stack.len() > max_sets {
+            return Search::Aborted(Fallback::TooManySets);
+        }
+        if eliminated == all {
+            let mut ordering = Vec::with_capacity(adjacency.len());
+            let mut set = all;
+            while set != 0 {
+                let v = last_eliminated[&set];
+                ordering.push(v);
+                set &= !(1 << v);
+            }
+            ordering.reverse();
+            return Search::Found(ordering);
+        }
+
+        let mut candidates = all & !eliminated;
+        while candidates != 0 {
+            let v = candidates.trailing_zeros() as usize;
+            candidates &= candidates - 1;
+            let next = eliminated | (1 << v);
+            if !last_eliminated.contains_key(&next) && bag_size(adjacency, eliminated, v) <= k {
+                last_eliminated.insert(next, v);
+                stack.push(next);
+            }
+        }
+    }
+    Search::Infeasible
+}
@@ -0,0 +1,116 @@
+//! Greedy elimination ordering heuristics. Ties are broken by the smallest vertex index.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap};
+use std::str::FromStr;
+
+use rustc_hash::FxHashSet;
+
+use super::{adjacency_sets, eliminate};
+use crate::Graph;
+
+/// Heuristic for computing an elimination ordering.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Heuristic {
+    /// Eliminates a vertex of minimum degree next.
+    MinDegree,
+    /// Eliminates a vertex whose elimination adds the fewest edges next.
+    #[default]
+    MinFill,
+    /// Maximum cardinality search: eliminates the vertices in reverse order of a search that always
+    /// visits a vertex with the most visited neighbors next.
+    Mcs
+}
+
+impl Heuristic {
+    /// All heuristics, e.g. for picking the one giving the smallest width.
+    pub const ALL: [Heuristic; 3] = [Heuristic::MinDegree, Heuristic::MinFill, Heuristic::Mcs];
+
+    /// Computes an elimination ordering of the vertices of `graph`.
+    pub fn ordering(self, graph: &Graph) -> Vec<usize> {
+        match self {
+            Heuristic::MinDegree => greedy_ordering(graph, |adjacency, v| adjacency[v].len()),
+            Heuristic::MinFill => greedy_ordering(graph, fill_in),
+            Heuristic::Mcs => mcs_ordering(graph)
+        }
+    }
+}
+
+impl FromStr for Heuristic {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "min-degree" => Ok(Heuristic::MinDegree),
+            "min-fill" => Ok(Heuristic::MinFill),
+            "mcs" => Ok(Heuristic::Mcs),
+            _ => Err(format!("Unknown heuristic {} (expected min-degree, min-fill or mcs)", s))
+        }
+    }
+}
+
+/// Repeatedly eliminates a vertex of minimum score. As eliminating a vertex only changes the neighborhoods
+/// of its neighbors, only the scores of vertices at distance at most two are updated. Vertices are kept in a heap
+/// in which an updated score is pushed as new entry, entries with outdated scores are skipped when popped.
+fn greedy_ordering(graph: &Graph, score: fn(&[FxHashSet<usize>], usize) -> usize) -> Vec<usize> {
+    let mut adjacency = adjacency_sets(graph);
+    let mut scores = (0..graph.size()).map(|v| score(&adjacency, v)).collect::<Vec<usize>>();
+    let mut heap = scores.iter().enumerate().map(|(v, score)| Reverse((*score, v))).collect::<BinaryHeap<_>>();
+    let mut eliminated = vec![false; graph.size()];
+    let mut ordering = Vec::with_capacity(graph.size());
+    while let Some(Reverse((v_score, v))) = heap.pop() {
+        if eliminated[v] || v_score != scores[v] { continue; }
+
+        eliminated[v] = true;
+        ordering.push(v);
+
+        let neighbors = eliminate(&mut adjacency, v);
+        let affected = neighbors.iter().flat_map(|u| adjacency[*u].iter().chain(Some(u))).copied().collect::<FxHashSet<usize>>();
+        for u in affected {
+            let new_score = score(&adjacency, u);
+            if new_score != scores[u] {
+                scores[u] = new_score;
+                heap.push(Reverse((new_score, u)));
+            }
+        }
+    }
+    ordering
+}
+
+/// Number of edges added when eliminating `v`.
+fn fill_in(adjacency: &[FxHashSet<usize>], v: usize) -> usize {
+    let neighbors = adjacency[v].iter().collect::<Vec<&usize>>();
+    let mut fill = 0;
+    for (i, u) in neighbors.iter().enumerate() {
+        fill += neighbors[i + 1..].iter().filter(|w| !adjacency[**u].contains(**w)).count();
+    }
+    fill
+}
+
+/// Keeps the unvisited vertices in buckets by their number of visited neighbors. This number only grows by one
+/// per step, so the highest non-empty bucket is found by moving down from one above the previous one.
+fn mcs_ordering(graph: &Graph) -> Vec<usize> {
+    let adjacency = adjacency_sets(graph);
+    let mut visited_neighbors = vec![0; graph.size()];
+    let mut visited = vec![false; graph.size()];
+    let mut buckets = vec![BTreeSet::new(); graph.size() + 1];
+    buckets[0].extend(0..graph.size());
+    let mut top = 0;
+    let mut ordering = Vec::with_capacity(graph.size());
+    while ordering.len() < graph.size() {
+        while buckets[top].is_empty() {
+            top -= 1;
+        }
+        let v = buckets[top].pop_first().unwrap();
+        visited[v] = true;
+        ordering.push(v);
+        for u in adjacency[v].iter().filter(|u| !visited[**u]) {
+            buckets[visited_neighbors[*u]].remove(u);
+            visited_neighbors[*u] += 1;
+            buckets[visited_neighbors[*u]].insert(*u);
+        }
+        top += 1;
+    }
+    ordering.reverse();
+    ordering
+}
@@ -0,0 +1,81 @@
+//! Computation of tree decompositions for graphs given without one.
+//!
+//! A tree decomposition is derived from an elimination ordering of the vertices: eliminating a vertex
+//! turns its remaining neighbors into a clique, and the vertex together with these neighbors forms a bag.
+
+pub mod exact;
+pub mod heuristics;
+
+use rustc_hash::FxHashSet;
+
+use crate::{Graph, InputTreeDecomposition, Result, TdIsError};
+
+pub use exact::{decompose_exact, ExactDecomposition, Fallback, DEFAULT_MAX_SETS};
+pub use heuristics::Heuristic;
+
+/// Computes a tree decomposition of `graph` from the elimination ordering of the given heuristic.
+pub fn decompose(graph: &Graph, heuristic: Heuristic) -> Result<InputTreeDecomposition> {
+    from_ordering(graph, &heuristic.ordering(graph))
+}
+
+/// Elimination ordering of the heuristic giving the tree decomposition of smallest width.
+fn best_heuristic_ordering(graph: &Graph) -> Result<Vec<usize>> {
+    let mut best: Option<(usize, Vec<usize>)> = None;
+    for heuristic in Heuristic::ALL {
+        let ordering = heuristic.ordering(graph);
+        let width = from_ordering(graph, &ordering)?.width();
+        if best.as_ref().is_none_or(|(best_width, _)| width < *best_width) {
+            best = Some((width, ordering));
+        }
+    }
+    Ok(best.map(|(_, ordering)| ordering).unwrap_or_default())
+}
+
+/// Computes the tree decomposition of `graph` induced by an elimination ordering, given as permutation of the vertex indices.
+/// The `i`-th bag contains the `i`-th eliminated vertex and its neighbors at the time of elimination and is attached
+/// to the bag of the neighbor eliminated first. Bags without such a neighbor are roots of separate trees.
+pub fn from_ordering(graph: &Graph, ordering: &[usize]) -> Result<InputTreeDecomposition> {
+    let mut position = vec![usize::MAX; graph.size()];
+    for (i, v) in ordering.iter().enumerate() {
+        if *v >= graph.size() || position[*v] != usize::MAX {
+            return Err(TdIsError::InvalidTd(format!("elimination ordering contains vertex index {} twice or out of range", v)));
+        }
+        position[*v] = i;
+    }
+    if ordering.len() != graph.size() {
+        return Err(TdIsError::InvalidTd(format!("elimination ordering has {} vertices, but the graph has {}", ordering.len(), graph.size())));
+    }
+
+    let mut adjacency = adjacency_sets(graph);
+    let mut bags = Vec::with_capacity(ordering.len());
+    let mut edges = vec![Vec::new(); ordering.len()];
+    for (i, v) in ordering.iter().enumerate() {
+        let neighbors = eliminate(&mut adjacency, *v);
+        if let Some(parent) = neighbors.iter().min_by_key(|u| position[**u]) {
+            edges[i].push(position[*parent]);
+        }
+
+        let mut bag = neighbors;
+        bag.push(*v);
+        bags.push(bag);
+    }
+
+    InputTreeDecomposition::from_bags(bags, edges, graph)
+}
+
+/// Neighborhoods of the vertices of `graph` as sets, without self-loops.
+pub(crate) fn adjacency_sets(graph: &Graph) -> Vec<FxHashSet<usize>> {
+    (0..graph.size())
+        .map(|v| graph.neighbors(v).iter().filter(|u| **u != v).copied().collect())
+        .collect()
+}
+
+/// Removes `v` from the graph given by `adjacency` after turning its neighbors into a clique and returns them.
+pub(crate) fn eliminate(adjacency: &mut [FxHashSet<usize>], v: usize) -> Vec<usize> {
+    let neighbors = adjacency[v].drain().collect::<Vec<usize>>();
+    for u in neighbors.iter() {
+        adjacency[*u].remove(&v);
+        adjacency[*u].extend(neighbors.iter().filter(|w| *w != u));
+    }
+    neighbors
+}
@@ -0,0 +1,61 @@
+use std::{fmt, io};
+
+use crate::datastructures::MAX_BAG_SIZE;
+
+/// Errors that can occur while reading inputs or solving an instance.
+#[derive(Debug)]
+pub enum TdIsError {
+    /// An input file could not be read.
+    Io { path: String, source: io::Error },
+    /// An output file could not be written.
+    Output { path: String, source: io::Error },
+    /// The format of an input file is neither given nor recognizable from its extension or content.
+    UnknownFormat { path: String },
+    /// A line of an input file could not be interpreted.
+    MalformedLine { path: String, line: usize, content: String },
+    /// A bag refers to a vertex that is not part of the graph.
+    UnknownVertex { path: String, line: usize, bag: String, vertex: String },
+    /// A vertex weight is not a non-negative integer.
+    BadWeight { path: String, line: usize, value: String },
+    /// A JSON document does not match the expected schema.
+    Json { path: String, message: String },
+    /// An XML document is malformed or does not match the expected schema.
+    Xml { path: String, message: String },
+    /// An elimination ordering does not list every vertex of the graph exactly once.
+    /// `line` is the line of the offending entry, if there is one.
+    InvalidOrdering { path: String, line: Option<usize>, message: String },
+    /// A bag has more vertices than subset masks have bits (128).
+    BagTooLarge { bag: String, size: usize },
+    /// The tree decomposition is not a valid tree decomposition of the graph.
+    InvalidTd(String)
+}
+
+pub type Result<T> = std::result::Result<T, TdIsError>;
+
+impl fmt::Display for TdIsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TdIsError::Io { path, source } => write!(f, "Could not read {}: {}", path, source),
+            TdIsError::Output { path, source } => write!(f, "Could not write {}: {}", path, source),
+            TdIsError::UnknownFormat { path } => write!(f, "{}: Unknown graph format, please specify it (--graph-format)", path),
+            TdIsError::MalformedLine { path, line, content } => write!(f, "{}:{}: Malformed line '{}'", path, line, content),
+            TdIsError::UnknownVertex { path, line, bag, vertex } => write!(f, "{}:{}: Bag {} contains unknown vertex '{}'", path, line, bag, vertex),
+            TdIsError::BadWeight { path, line, value } => write!(f, "{}:{}: Weight '{}' is not a non-negative integer", path, line, value),
+            TdIsError::Json { path, message } => write!(f, "{}: Invalid JSON document: {}", path, message),
+            TdIsError::Xml { path, message } => write!(f, "{}: Invalid XML document: {}", path, message),
+            TdIsError::InvalidOrdering { path, line: Some(line), message } => write!(f, "{}:{}: Invalid elimination ordering: {}", path, line, message),
+            TdIsError::InvalidOrdering { path, line: None, message } => write!(f, "{}: Invalid elimination ordering: {}", path, message),
+            TdIsError::BagTooLarge { bag, size } => write!(f, "Bag {} has {} vertices, but at most {} are supported", bag, size, MAX_BAG_SIZE),
+            TdIsError::InvalidTd(msg) => write!(f, "Invalid tree decomposition: {}", msg)
+        }
+    }
+}
+
+impl std::error::Error for TdIsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TdIsError::Io { source, .. } | TdIsError::Output { source, .. } => Some(source),
+            _ => None
+        }
+    }
+}
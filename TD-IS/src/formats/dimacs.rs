@@ -0,0 +1,41 @@
+//! DIMACS graph format as used by the maximum (weight) clique and independent set benchmarks (`.col`, `.clq`).
+//!
+//! A `p edge <n> <m>` (or `p col <n> <m>`) line is followed by `e <u> <v>` lines for the edges and
+//! optional `n <v> <weight>` lines for the vertex weights, vertices are numbered from 1.
+//! Vertices without an `n` line get a weight by the [`DefaultWeight`] rule.
+
+use super::{parse_index, DefaultWeight};
+use crate::{Graph, GraphBuilder, Result, TdIsError};
+
+/// Parses a graph in DIMACS format. `path` is only used in error messages.
+pub fn parse_graph(content: &str, path: &str, default_weight: DefaultWeight) -> Result<Graph> {
+    let mut builder = GraphBuilder::new();
+    let mut num_vertices = None;
+    for (line_idx, line) in content.lines().enumerate() {
+        let malformed = || TdIsError::MalformedLine { path: path.to_string(), line: line_idx + 1, content: line.to_string() };
+        let tokens = line.split_whitespace().collect::<Vec<&str>>();
+        match tokens.as_slice() {
+            [] | ["c", ..] => continue,
+            ["p", "edge" | "col", n, _] if num_vertices.is_none() => {
+                let n = n.parse::<usize>().map_err(|_| malformed())?;
+                for v in 0..n {
+                    builder.add_vertex(&(v + 1).to_string(), default_weight.weight(v));
+                }
+                num_vertices = Some(n);
+            },
+            ["e", u, v] => {
+                let u = parse_index(u, num_vertices).ok_or_else(malformed)?;
+                let v = parse_index(v, num_vertices).ok_or_else(malformed)?;
+                builder.add_edge(u, v);
+            },
+            ["n", v, w] => {
+                let v = parse_index(v, num_vertices).ok_or_else(malformed)?;
+                let weight = w.parse::<usize>().map_err(|_| TdIsError::BadWeight { path: path.to_string(), line: line_idx + 1, value: w.to_string() })?;
+                builder.set_weight(v, weight);
+            },
+            _ => return Err(malformed())
+        }
+    }
+
+    Ok(builder.build())
+}
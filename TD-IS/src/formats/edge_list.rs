@@ -0,0 +1,36 @@
+//! Plain edge lists with one `<u> <v>` edge per line, separated by whitespace or a comma.
+//!
+//! Vertices are named by their tokens and get a weight by the [`DefaultWeight`] rule in order of first occurrence.
+//! Further columns (e.g. edge weights) are ignored, lines starting with `#` or `%` are comments.
+
+use super::DefaultWeight;
+use crate::{Graph, GraphBuilder, Result};
+
+/// Parses a graph given as edge list.
+pub fn parse_graph(content: &str, default_weight: DefaultWeight) -> Result<Graph> {
+    let mut builder = GraphBuilder::new();
+    for line in content.lines() {
+        if line.starts_with('#') || line.starts_with('%') { continue; }
+
+        let tokens = line.split(|c: char| c.is_whitespace() || c == ',').filter(|t| !t.is_empty()).collect::<Vec<&str>>();
+        match tokens.as_slice() {
+            [] => continue,
+            [u, v, ..] => {
+                let u = vertex(&mut builder, u, default_weight);
+                let v = vertex(&mut builder, v, default_weight);
+                builder.add_edge(u, v);
+            },
+            [v] => { vertex(&mut builder, v, default_weight); }    // Isolated vertex
+        }
+    }
+    Ok(builder.build())
+}
+
+fn vertex(builder: &mut GraphBuilder, name: &str, default_weight: DefaultWeight) -> usize {
+    let idx = builder.size();
+    let v = builder.get_or_add_vertex(name);
+    if v == idx {
+        builder.set_weight(v, default_weight.weight(v));
+    }
+    v
+}
@@ -0,0 +1,101 @@
+//! GraphML documents as exported by common graph tools (`.graphml`).
+//!
+//! Vertex weights are read from the `<data>` element of a node attribute, declared by a `<key>` whose
+//! `attr.name` or `id` matches the given weight attribute, falling back to the `<default>` of the key and then
+//! the [`DefaultWeight`] rule. Vertices are named by their node id or, if given, by a label attribute.
+//! Edges are treated as undirected, nested graphs and hyperedges are not supported.
+
+use roxmltree::{Document, Node};
+use rustc_hash::FxHashMap;
+
+use super::DefaultWeight;
+use crate::{Graph, GraphBuilder, Result, TdIsError};
+
+/// Weight attribute used if none is given.
+pub const DEFAULT_WEIGHT_ATTRIBUTE: &str = "weight";
+
+/// A node attribute declared by a `<key>` element.
+struct Key<'a> {
+    id: &'a str,
+    default: Option<&'a str>
+}
+
+/// Parses a GraphML document. `path` is only used in error messages.
+/// Explicitly given weight and label attributes have to be declared, while vertices only get default weights
+/// if the weight attribute is not given and no [`DEFAULT_WEIGHT_ATTRIBUTE`] is declared.
+pub fn parse_graph(content: &str, path: &str, weight_attribute: Option<&str>, label_attribute: Option<&str>, default_weight: DefaultWeight) -> Result<Graph> {
+    let doc = Document::parse(content).map_err(|e| xml_error(path, e.to_string()))?;
+    let declared_key = |name: &str| find_key(&doc, name).ok_or_else(|| xml_error(path, format!("no node attribute '{}' declared", name)));
+    let weight_key = match weight_attribute {
+        Some(name) => Some(declared_key(name)?),
+        None => find_key(&doc, DEFAULT_WEIGHT_ATTRIBUTE)
+    };
+    let label_key = match label_attribute {
+        Some(name) => Some(declared_key(name)?),
+        None => None
+    };
+
+    let mut builder = GraphBuilder::new();
+    let mut node_indices = FxHashMap::default();
+    for node in doc.descendants().filter(|n| n.has_tag_name("node")) {
+        let id = node.attribute("id").ok_or_else(|| xml_error(path, format!("node without id at {}", doc.text_pos_at(node.range().start))))?;
+        let name = match &label_key {
+            Some(key) => data(node, key).ok_or_else(|| xml_error(path, format!("node '{}' has no label", id)))?,
+            None => id
+        };
+        let idx = builder.size();
+        if builder.get_or_add_vertex(name) != idx {
+            return Err(xml_error(path, format!("vertex name '{}' is used by several nodes", name)));
+        }
+        if node_indices.insert(id, idx).is_some() {
+            return Err(xml_error(path, format!("node id '{}' is used by several nodes", id)));
+        }
+
+        let weight = match weight_key.as_ref().and_then(|key| data(node, key)) {
+            Some(value) => value.trim().parse::<usize>().map_err(|_| TdIsError::BadWeight {
+                path: path.to_string(),
+                line: doc.text_pos_at(node.range().start).row as usize,
+                value: value.to_string()
+            })?,
+            None => default_weight.weight(idx)
+        };
+        builder.set_weight(idx, weight);
+    }
+
+    for edge in doc.descendants().filter(|n| n.has_tag_name("edge")) {
+        let endpoint = |attribute: &str| {
+            edge.attribute(attribute)
+                .and_then(|id| node_indices.get(id).copied())
+                .ok_or_else(|| xml_error(path, format!("edge at {} has no known {}", doc.text_pos_at(edge.range().start), attribute)))
+        };
+        let u = endpoint("source")?;
+        let v = endpoint("target")?;
+        builder.add_edge(u, v);
+    }
+
+    Ok(builder.build())
+}
+
+/// Finds the node attribute whose name or id is `name`.
+fn find_key<'a>(doc: &'a Document, name: &str) -> Option<Key<'a>> {
+    doc.descendants()
+        .filter(|n| n.has_tag_name("key"))
+        .filter(|n| matches!(n.attribute("for"), None | Some("node") | Some("all")))
+        .find(|n| n.attribute("attr.name") == Some(name) || n.attribute("id") == Some(name))
+        .and_then(|n| Some(Key {
+            id: n.attribute("id")?,
+            default: n.children().find(|c| c.has_tag_name("default")).and_then(|c| c.text())
+        }))
+}
+
+/// Value of the attribute `key` of `node`, or the default value of the attribute.
+fn data<'a>(node: Node<'a, '_>, key: &Key<'a>) -> Option<&'a str> {
+    node.children()
+        .find(|c| c.has_tag_name("data") && c.attribute("key") == Some(key.id))
+        .map(|c| c.text().unwrap_or(""))
+        .or(key.default)
+}
+
+fn xml_error(path: &str, message: String) -> TdIsError {
+    TdIsError::Xml { path: path.to_string(), message }
+}
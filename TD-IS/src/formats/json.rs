@@ -0,0 +1,133 @@
+//! JSON documents containing a graph together with a tree decomposition of it:
+//!
+//! ```json
+//! {
+//!     "vertices": [{"name": "a", "weight": 3}, {"name": "b", "weight": 2}, {"name": "c"}],
+//!     "edges": [["a", "b"], ["b", "c"]],
+//!     "bags": [{"name": "1", "vertices": ["a", "b"]}, {"name": "2", "vertices": ["b", "c"]}],
+//!     "bag_edges": [["1", "2"]]
+//! }
+//! ```
+//!
+//! Vertices without a weight get one by the [`DefaultWeight`] rule, `edges` and `bag_edges` may be omitted.
+//! Solutions are written as `{"objective": 4, "cardinality": 2, "vertices": ["a", "c"], "vertex_indices": [0, 2], ...}`
+//! together with the objective value of every tree of the decomposition, its width and the running times in seconds.
+
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use super::{DefaultWeight, RunStats};
+use crate::{Graph, GraphBuilder, InputTreeDecomposition, Result, Solution, TdIsError};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Instance {
+    vertices: Vec<Vertex>,
+    #[serde(default)]
+    edges: Vec<(String, String)>,
+    bags: Vec<NamedBag>,
+    #[serde(default)]
+    bag_edges: Vec<(String, String)>
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Vertex {
+    name: String,
+    weight: Option<usize>
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NamedBag {
+    name: String,
+    vertices: Vec<String>
+}
+
+#[derive(Serialize)]
+struct SolutionOutput<'a> {
+    objective: usize,
+    cardinality: usize,
+    vertices: &'a Vec<String>,
+    vertex_indices: &'a Vec<usize>,
+    component_objectives: &'a Vec<usize>,
+    width: usize,
+    timings: Timings
+}
+
+#[derive(Serialize)]
+struct Timings {
+    parse: f64,
+    decomposition: f64,
+    solve: f64,
+    total: f64
+}
+
+/// Parses a JSON document into the graph and the tree decomposition it contains.
+pub fn parse_instance(content: &str, path: &str, default_weight: DefaultWeight) -> Result<(Graph, InputTreeDecomposition)> {
+    let instance: Instance = serde_json::from_str(content).map_err(|e| json_error(path, e.to_string()))?;
+
+    let mut builder = GraphBuilder::new();
+    let mut vertex_indices = FxHashMap::default();
+    for (idx, vertex) in instance.vertices.iter().enumerate() {
+        if vertex_indices.insert(vertex.name.as_str(), idx).is_some() {
+            return Err(json_error(path, format!("vertex '{}' is listed twice", vertex.name)));
+        }
+        builder.add_vertex(&vertex.name, vertex.weight.unwrap_or_else(|| default_weight.weight(idx)));
+    }
+    for (u, v) in instance.edges.iter() {
+        let u = lookup(&vertex_indices, u, path, "edge")?;
+        let v = lookup(&vertex_indices, v, path, "edge")?;
+        builder.add_edge(u, v);
+    }
+    let graph = builder.build();
+
+    let mut bag_indices = FxHashMap::default();
+    let mut bags = Vec::with_capacity(instance.bags.len());
+    for (idx, bag) in instance.bags.iter().enumerate() {
+        if bag_indices.insert(bag.name.as_str(), idx).is_some() {
+            return Err(json_error(path, format!("bag '{}' is listed twice", bag.name)));
+        }
+        let vertices = bag.vertices.iter()
+            .map(|v| lookup(&vertex_indices, v, path, &format!("bag '{}'", bag.name)))
+            .collect::<Result<Vec<usize>>>()?;
+        bags.push(vertices);
+    }
+    let mut bag_edges = vec![Vec::new(); bags.len()];
+    for (a, b) in instance.bag_edges.iter() {
+        let a = lookup(&bag_indices, a, path, "bag edge")?;
+        let b = lookup(&bag_indices, b, path, "bag edge")?;
+        bag_edges[a].push(b);
+    }
+
+    let bag_names = instance.bags.into_iter().map(|bag| bag.name).collect();
+    let input_td = InputTreeDecomposition::from_named_bags(bag_names, bags, bag_edges, &graph)?;
+    Ok((graph, input_td))
+}
+
+/// Writes a solution together with the statistics of its run as JSON document.
+pub fn write_solution(solution: &Solution, stats: &RunStats) -> String {
+    let output = SolutionOutput {
+        objective: solution.weight(),
+        cardinality: solution.vertices().len(),
+        vertices: solution.vertex_names(),
+        vertex_indices: solution.vertices(),
+        component_objectives: solution.component_weights(),
+        width: stats.width,
+        timings: Timings {
+            parse: stats.parse_time.as_secs_f64(),
+            decomposition: stats.decomposition_time.as_secs_f64(),
+            solve: stats.solve_time.as_secs_f64(),
+            total: (stats.parse_time + stats.decomposition_time + stats.solve_time).as_secs_f64()
+        }
+    };
+    serde_json::to_string_pretty(&output).expect("solution is serializable")
+}
+
+fn lookup(indices: &FxHashMap<&str, usize>, name: &str, path: &str, context: &str) -> Result<usize> {
+    indices.get(name).copied().ok_or_else(|| json_error(path, format!("{} refers to unknown name '{}'", context, name)))
+}
+
+fn json_error(path: &str, message: String) -> TdIsError {
+    TdIsError::Json { path: path.to_string(), message }
+}
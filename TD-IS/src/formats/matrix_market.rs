@@ -0,0 +1,46 @@
+//! MatrixMarket coordinate format (`.mtx`) for adjacency matrices.
+//!
+//! After the `%%MatrixMarket matrix coordinate <field> <symmetry>` banner and `%` comments, a `<rows> <cols> <entries>` line
+//! is followed by one `<i> <j> [value]` line per nonzero entry, indices start at 1. Every off-diagonal entry is an edge,
+//! values and diagonal entries are ignored. Vertices get a weight by the [`DefaultWeight`] rule.
+
+use super::{parse_index, DefaultWeight};
+use crate::{Graph, GraphBuilder, Result, TdIsError};
+
+/// Parses a graph from a MatrixMarket adjacency matrix. `path` is only used in error messages.
+pub fn parse_graph(content: &str, path: &str, default_weight: DefaultWeight) -> Result<Graph> {
+    let mut builder = GraphBuilder::new();
+    let mut num_vertices = None;
+    for (line_idx, line) in content.lines().enumerate() {
+        let malformed = || TdIsError::MalformedLine { path: path.to_string(), line: line_idx + 1, content: line.to_string() };
+        if line_idx == 0 {
+            let banner = line.to_lowercase();
+            if !banner.starts_with("%%matrixmarket matrix coordinate") { return Err(malformed()); }
+            continue;
+        }
+        if line.starts_with('%') { continue; }
+
+        let tokens = line.split_whitespace().collect::<Vec<&str>>();
+        match (tokens.as_slice(), num_vertices) {
+            ([], _) => continue,
+            ([rows, cols, _], None) => {
+                if rows != cols { return Err(malformed()); }   // Adjacency matrices are square
+                let n = rows.parse::<usize>().map_err(|_| malformed())?;
+                for v in 0..n {
+                    builder.add_vertex(&(v + 1).to_string(), default_weight.weight(v));
+                }
+                num_vertices = Some(n);
+            },
+            ([i, j, ..], Some(_)) => {
+                let u = parse_index(i, num_vertices).ok_or_else(malformed)?;
+                let v = parse_index(j, num_vertices).ok_or_else(malformed)?;
+                if u != v {
+                    builder.add_edge(u, v);
+                }
+            },
+            _ => return Err(malformed())
+        }
+    }
+
+    Ok(builder.build())
+}
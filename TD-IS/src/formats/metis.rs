@@ -0,0 +1,72 @@
+//! METIS graph format (`.graph`).
+//!
+//! The header `<n> <m> [fmt [ncon]]` is followed by one line per vertex listing its neighbors, vertices are numbered from 1.
+//! If the second digit of `fmt` is set, every vertex line starts with `ncon` vertex weights of which the first one is used;
+//! if the first digit is set, a vertex size precedes them and if the last digit is set, every neighbor is followed by an edge weight.
+//! Without vertex weights, vertices get a weight by the [`DefaultWeight`] rule. Lines starting with `%` are comments.
+
+use super::{parse_index, DefaultWeight};
+use crate::{Graph, GraphBuilder, Result, TdIsError};
+
+/// Parses a graph in METIS format. `path` is only used in error messages.
+pub fn parse_graph(content: &str, path: &str, default_weight: DefaultWeight) -> Result<Graph> {
+    let mut builder = GraphBuilder::new();
+    let mut lines = content.lines().enumerate().filter(|(_, line)| !line.starts_with('%'));
+
+    let (header_idx, header) = lines.next().ok_or_else(|| TdIsError::MalformedLine { path: path.to_string(), line: 1, content: String::new() })?;
+    let malformed = |line_idx: usize, line: &str| TdIsError::MalformedLine { path: path.to_string(), line: line_idx + 1, content: line.to_string() };
+    let header_tokens = header.split_whitespace().collect::<Vec<&str>>();
+    if header_tokens.len() < 2 || header_tokens.len() > 4 {
+        return Err(malformed(header_idx, header));
+    }
+    let n = header_tokens[0].parse::<usize>().map_err(|_| malformed(header_idx, header))?;
+    let fmt = format!("{:0>3}", header_tokens.get(2).unwrap_or(&"0"));
+    if fmt.len() != 3 || fmt.chars().any(|c| c != '0' && c != '1') {
+        return Err(malformed(header_idx, header));
+    }
+    let has_sizes = fmt.as_bytes()[0] == b'1';
+    let has_vertex_weights = fmt.as_bytes()[1] == b'1';
+    let has_edge_weights = fmt.as_bytes()[2] == b'1';
+    let num_weights = match header_tokens.get(3) {
+        Some(ncon) => ncon.parse::<usize>().map_err(|_| malformed(header_idx, header))?,
+        None => 1
+    };
+    if has_vertex_weights && num_weights == 0 {
+        return Err(malformed(header_idx, header));
+    }
+
+    for v in 0..n {
+        builder.add_vertex(&(v + 1).to_string(), default_weight.weight(v));
+    }
+
+    for v in 0..n {
+        let (line_idx, line) = match lines.next() {
+            Some(line) => line,
+            None => break   // Missing trailing lines of isolated vertices
+        };
+        let mut tokens = line.split_whitespace();
+        if has_sizes {
+            tokens.next().ok_or_else(|| malformed(line_idx, line))?;
+        }
+        if has_vertex_weights {
+            let w = tokens.next().ok_or_else(|| malformed(line_idx, line))?;
+            let weight = w.parse::<usize>().map_err(|_| TdIsError::BadWeight { path: path.to_string(), line: line_idx + 1, value: w.to_string() })?;
+            builder.set_weight(v, weight);
+            for _ in 1..num_weights {
+                tokens.next().ok_or_else(|| malformed(line_idx, line))?;
+            }
+        }
+        while let Some(u) = tokens.next() {
+            let u = parse_index(u, Some(n)).ok_or_else(|| malformed(line_idx, line))?;
+            builder.add_edge(v, u);
+            if has_edge_weights {
+                tokens.next().ok_or_else(|| malformed(line_idx, line))?;
+            }
+        }
+    }
+    if let Some((line_idx, line)) = lines.find(|(_, line)| !line.trim().is_empty()) {
+        return Err(malformed(line_idx, line));  // More vertex lines than vertices
+    }
+
+    Ok(builder.build())
+}
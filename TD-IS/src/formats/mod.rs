@@ -0,0 +1,408 @@
+//! Readers for the supported graph and tree decomposition file formats.
+
+pub mod dimacs;
+pub mod edge_list;
+pub mod graphml;
+pub mod json;
+pub mod matrix_market;
+pub mod metis;
+pub mod ordering;
+pub mod pace;
+pub mod sol;
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::{Graph, InputTreeDecomposition, Result, Solution, TdIsError};
+
+/// File format of an input graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// `vertex,,weight` and `vertex,vertex` lines, see [`Graph::new`].
+    Csv,
+    /// PACE 2017 `.gr` format, see [`pace::parse_graph`].
+    Pace,
+    /// DIMACS `.col`/`.clq` format, see [`dimacs::parse_graph`].
+    Dimacs,
+    /// METIS `.graph` format, see [`metis::parse_graph`].
+    Metis,
+    /// Plain edge list, see [`edge_list::parse_graph`].
+    EdgeList,
+    /// MatrixMarket `.mtx` adjacency matrix, see [`matrix_market::parse_graph`].
+    MatrixMarket,
+    /// GraphML document, see [`graphml::parse_graph`].
+    GraphMl
+}
+
+/// File format of an input tree decomposition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TdFormat {
+    /// `bag,,v1;v2;...` and `bag,bag` lines, see [`InputTreeDecomposition::new`].
+    Csv,
+    /// PACE 2017 `.td` format, see [`pace::parse_td`].
+    Pace
+}
+
+/// Format of the solution output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// `obj: <weight>` followed by the comma separated names of the chosen vertices.
+    Text,
+    /// JSON document with the solution and statistics of the run, see [`json::write_solution`].
+    Json,
+    /// Line-oriented solution file, see [`sol::write_solution`].
+    Sol
+}
+
+impl GraphFormat {
+    /// Guesses the format from the file extension.
+    pub fn from_extension(path: &str) -> Option<Self> {
+        match extension(path)?.as_str() {
+            "csv" => Some(GraphFormat::Csv),
+            "gr" => Some(GraphFormat::Pace),
+            "col" | "clq" | "dimacs" => Some(GraphFormat::Dimacs),
+            "graph" | "metis" => Some(GraphFormat::Metis),
+            "edges" | "el" | "edgelist" => Some(GraphFormat::EdgeList),
+            "mtx" => Some(GraphFormat::MatrixMarket),
+            "graphml" | "xml" => Some(GraphFormat::GraphMl),
+            _ => None
+        }
+    }
+
+    /// Guesses the format from the content. METIS files are told apart from edge lists by their header
+    /// and vertex lines, see [`Self::metis_or_edge_list`]. Returns `None` if the format is unclear, e.g. for JSON instances.
+    pub fn from_header(content: &str) -> Option<Self> {
+        if is_instance_content(content) {
+            return None;
+        }
+        if content.trim_start().to_lowercase().starts_with("%%matrixmarket") {
+            return Some(GraphFormat::MatrixMarket);
+        }
+        if content.trim_start().starts_with('<') {
+            return Some(GraphFormat::GraphMl);
+        }
+
+        let line = content.lines().find(|line| !line.trim().is_empty() && !is_c_comment(line) && !is_comment(line))?;
+        let tokens = line.split_whitespace().collect::<Vec<&str>>();
+        match tokens.as_slice() {
+            ["p", "tw", ..] => Some(GraphFormat::Pace),
+            ["p", "edge" | "col", ..] => Some(GraphFormat::Dimacs),
+            _ if line.contains(',') => Some(GraphFormat::Csv),
+            _ => Self::metis_or_edge_list(content)
+        }
+    }
+
+    /// A METIS header `<n> <m> [fmt [ncon]]` has to be followed by exactly `n` vertex lines listing `2m` neighbors in total.
+    /// Other content is an edge list if all of its lines have the same number of at least two columns.
+    fn metis_or_edge_list(content: &str) -> Option<Self> {
+        let mut lines = content.lines().filter(|line| !is_comment(line)).skip_while(|line| line.trim().is_empty());
+        let header = lines.next()?.split_whitespace().collect::<Vec<&str>>();
+        let body = lines.collect::<Vec<&str>>();
+        if is_metis(&header, &body) {
+            return Some(GraphFormat::Metis);
+        }
+
+        let mut columns = std::iter::once(header.len())
+            .chain(body.iter().filter(|line| !line.trim().is_empty()).map(|line| line.split_whitespace().count()));
+        let first = columns.next()?;
+        (first >= 2 && columns.all(|c| c == first)).then_some(GraphFormat::EdgeList)
+    }
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(GraphFormat::Csv),
+            "pace" | "gr" => Ok(GraphFormat::Pace),
+            "dimacs" => Ok(GraphFormat::Dimacs),
+            "metis" => Ok(GraphFormat::Metis),
+            "edgelist" => Ok(GraphFormat::EdgeList),
+            "mtx" => Ok(GraphFormat::MatrixMarket),
+            "graphml" => Ok(GraphFormat::GraphMl),
+            _ => Err(format!("Unknown graph format {} (expected csv, pace, dimacs, metis, edgelist, mtx or graphml)", s))
+        }
+    }
+}
+
+impl TdFormat {
+    /// Guesses the format from the file extension.
+    pub fn from_extension(path: &str) -> Option<Self> {
+        match extension(path)?.as_str() {
+            "csv" => Some(TdFormat::Csv),
+            "td" => Some(TdFormat::Pace),
+            _ => None
+        }
+    }
+
+    /// Guesses the format from the first lines of the content.
+    pub fn from_header(content: &str) -> Option<Self> {
+        let line = content.lines().find(|line| !line.trim().is_empty() && !is_c_comment(line))?;
+        if line.starts_with("s td") {
+            Some(TdFormat::Pace)
+        } else if line.contains(',') {
+            Some(TdFormat::Csv)
+        } else {
+            None
+        }
+    }
+}
+
+impl OutputFormat {
+    /// Guesses the format from the extension of the output file.
+    pub fn from_extension(path: &str) -> Option<Self> {
+        match extension(path)?.as_str() {
+            "json" => Some(OutputFormat::Json),
+            "sol" => Some(OutputFormat::Sol),
+            "txt" => Some(OutputFormat::Text),
+            _ => None
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "sol" => Ok(OutputFormat::Sol),
+            _ => Err(format!("Unknown output format {} (expected text, json or sol)", s))
+        }
+    }
+}
+
+/// Weight rule for vertices whose weight is not given in the input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DefaultWeight {
+    /// Every vertex has weight 1.
+    #[default]
+    Unit,
+    /// The `i`-th vertex (counting from 1) has weight `i mod 200 + 1`, as common in the weighted benchmark literature.
+    Modulo200
+}
+
+impl DefaultWeight {
+    /// Weight of the vertex with 0-based index `idx`.
+    pub fn weight(self, idx: usize) -> usize {
+        match self {
+            DefaultWeight::Unit => 1,
+            DefaultWeight::Modulo200 => (idx + 1) % 200 + 1
+        }
+    }
+}
+
+impl FromStr for DefaultWeight {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "unit" => Ok(DefaultWeight::Unit),
+            "mod200" => Ok(DefaultWeight::Modulo200),
+            _ => Err(format!("Unknown default weight rule {} (expected unit or mod200)", s))
+        }
+    }
+}
+
+/// Options for reading inputs that are not specific to one file.
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+    /// Format of the graph, detected from the file extension or content if not given.
+    pub graph_format: Option<GraphFormat>,
+    /// Weight rule for formats in which vertex weights are optional.
+    pub default_weight: DefaultWeight,
+    /// Node attribute holding the vertex weights in GraphML documents, [`graphml::DEFAULT_WEIGHT_ATTRIBUTE`] if not given.
+    pub weight_attribute: Option<String>,
+    /// Node attribute naming the vertices in GraphML documents, the node id if not given.
+    pub label_attribute: Option<String>
+}
+
+/// Statistics of a run that are reported alongside the solution.
+#[derive(Debug, Clone, Default)]
+pub struct RunStats {
+    /// Width of the input tree decomposition.
+    pub width: usize,
+    /// Time spent reading the inputs.
+    pub parse_time: Duration,
+    /// Time spent validating the input tree decomposition and constructing the nice tree decomposition.
+    pub decomposition_time: Duration,
+    /// Time spent solving and retrieving the solution.
+    pub solve_time: Duration
+}
+
+/// Writes a solution of `graph` in the given format.
+pub fn write_solution(format: OutputFormat, solution: &Solution, graph: &Graph, stats: &RunStats) -> String {
+    match format {
+        OutputFormat::Text => format!("{}\n", solution),
+        OutputFormat::Json => format!("{}\n", json::write_solution(solution, stats)),
+        OutputFormat::Sol => sol::write_solution(solution, graph)
+    }
+}
+
+/// Path that stands for the standard input.
+pub const STDIN_PATH: &str = "-";
+
+/// Reads the content of an input file, or of the standard input if `path` is [`STDIN_PATH`].
+/// gzip and xz compressed content is recognized by its magic bytes and decompressed.
+pub fn read_input(path: &str) -> Result<String> {
+    let io_error = |source| TdIsError::Io { path: path.to_string(), source };
+    let mut bytes = Vec::new();
+    if path == STDIN_PATH {
+        io::stdin().lock().read_to_end(&mut bytes).map_err(io_error)?;
+    } else {
+        File::open(path).and_then(|mut file| file.read_to_end(&mut bytes)).map_err(io_error)?;
+    }
+
+    let mut content = String::new();
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        flate2::read::MultiGzDecoder::new(bytes.as_slice()).read_to_string(&mut content).map_err(io_error)?;
+    } else if bytes.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+        xz2::read::XzDecoder::new_multi_decoder(bytes.as_slice()).read_to_string(&mut content).map_err(io_error)?;
+    } else {
+        content = String::from_utf8(bytes).map_err(|e| io_error(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+    }
+    Ok(content)
+}
+
+/// Reads a graph, detecting its format from the file extension or content unless given in `options`.
+pub fn read_graph(path: &str, options: &ReadOptions) -> Result<Graph> {
+    eprintln!("Parsing graph from {}", path);
+    parse_graph(&read_input(path)?, path, options)
+}
+
+/// Reads a JSON instance if the file is one by its extension or content (a leading `{`), returning its graph
+/// and tree decomposition, and a graph as [`read_graph`] otherwise.
+pub fn read_graph_or_instance(path: &str, options: &ReadOptions) -> Result<(Graph, Option<InputTreeDecomposition>)> {
+    let content = read_input(path)?;
+    if options.graph_format.is_none() && (is_instance(path) || is_instance_content(&content)) {
+        eprintln!("Parsing instance from {}", path);
+        let (graph, input_td) = json::parse_instance(&content, path, options.default_weight)?;
+        Ok((graph, Some(input_td)))
+    } else {
+        eprintln!("Parsing graph from {}", path);
+        Ok((parse_graph(&content, path, options)?, None))
+    }
+}
+
+/// Parses a graph, detecting its format from the file extension or content unless given in `options`.
+fn parse_graph(content: &str, path: &str, options: &ReadOptions) -> Result<Graph> {
+    let format = options.graph_format
+        .or_else(|| GraphFormat::from_extension(path))
+        .or_else(|| GraphFormat::from_header(content))
+        .ok_or_else(|| TdIsError::UnknownFormat { path: path.to_string() })?;
+
+    match format {
+        GraphFormat::Csv => Graph::from_csv(content, path),
+        GraphFormat::Pace => pace::parse_graph(content, path, options.default_weight),
+        GraphFormat::Dimacs => dimacs::parse_graph(content, path, options.default_weight),
+        GraphFormat::Metis => metis::parse_graph(content, path, options.default_weight),
+        GraphFormat::EdgeList => edge_list::parse_graph(content, options.default_weight),
+        GraphFormat::MatrixMarket => matrix_market::parse_graph(content, path, options.default_weight),
+        GraphFormat::GraphMl => graphml::parse_graph(
+            content,
+            path,
+            options.weight_attribute.as_deref(),
+            options.label_attribute.as_deref(),
+            options.default_weight
+        )
+    }
+}
+
+/// Reads a tree decomposition of `graph`, detecting its format from the file extension or content.
+/// Files of unknown format are read as CSV.
+pub fn read_td(path: &str, graph: &Graph) -> Result<InputTreeDecomposition> {
+    eprintln!("Parsing treedecomposition from {}", path);
+    let content = read_input(path)?;
+    let format = TdFormat::from_extension(path)
+        .or_else(|| TdFormat::from_header(&content))
+        .unwrap_or(TdFormat::Csv);
+
+    match format {
+        TdFormat::Csv => InputTreeDecomposition::from_csv(&content, path, graph),
+        TdFormat::Pace => pace::parse_td(&content, path, graph)
+    }
+}
+
+/// Reads an elimination ordering of the vertices of `graph`, see [`ordering`].
+pub fn read_ordering(path: &str, graph: &Graph) -> Result<Vec<usize>> {
+    eprintln!("Parsing elimination ordering from {}", path);
+    ordering::parse_ordering(&read_input(path)?, path, graph)
+}
+
+/// Reads a JSON document containing both a graph and a tree decomposition of it, see [`json`].
+pub fn read_instance(path: &str, options: &ReadOptions) -> Result<(Graph, InputTreeDecomposition)> {
+    eprintln!("Parsing instance from {}", path);
+    let content = read_input(path)?;
+    json::parse_instance(&content, path, options.default_weight)
+}
+
+/// Whether the file is a JSON instance, judging by its extension.
+pub fn is_instance(path: &str) -> bool {
+    extension(path).is_some_and(|e| e == "json")
+}
+
+/// Whether the content is a JSON document, i.e. starts with `{`.
+fn is_instance_content(content: &str) -> bool {
+    content.trim_start().starts_with('{')
+}
+
+/// Whether the METIS header given by its tokens matches the vertex lines in `body`, see [`metis`].
+fn is_metis(header: &[&str], body: &[&str]) -> bool {
+    let numbers = header.iter().map(|t| t.parse::<usize>().ok()).collect::<Option<Vec<usize>>>();
+    let (n, m) = match numbers.as_deref() {
+        Some([n, m, ..]) if header.len() <= 4 => (*n, *m),
+        _ => return false
+    };
+    let fmt = format!("{:0>3}", header.get(2).unwrap_or(&"0"));
+    if fmt.len() != 3 || fmt.chars().any(|c| c != '0' && c != '1') {
+        return false;
+    }
+    let ncon = header.get(3).map_or(1, |ncon| ncon.parse::<usize>().unwrap_or(0));
+    let prefix = usize::from(fmt.as_bytes()[0] == b'1') + if fmt.as_bytes()[1] == b'1' { ncon } else { 0 };
+    let entries_per_neighbor = if fmt.as_bytes()[2] == b'1' { 2 } else { 1 };
+    if body.len() < n || body[n..].iter().any(|line| !line.trim().is_empty()) {
+        return false;
+    }
+
+    let mut degree_sum = 0;
+    for line in body[..n].iter() {
+        let tokens = line.split_whitespace().collect::<Vec<&str>>();
+        if tokens.len() < prefix || (tokens.len() - prefix) % entries_per_neighbor != 0 || tokens.iter().any(|t| t.parse::<f64>().is_err()) {
+            return false;
+        }
+        degree_sum += (tokens.len() - prefix) / entries_per_neighbor;
+    }
+    degree_sum == 2 * m
+}
+
+/// Whether the line is a comment of METIS files or edge lists, i.e. starts with `%` or `#`.
+fn is_comment(line: &str) -> bool {
+    line.starts_with('%') || line.starts_with('#')
+}
+
+/// Whether the line is a DIMACS/PACE comment, i.e. its first token is `c`.
+fn is_c_comment(line: &str) -> bool {
+    line.split_whitespace().next() == Some("c")
+}
+
+/// Parses a 1-based index into a 0-based one, given the header has been read and the index is in range.
+fn parse_index(token: &str, count: Option<usize>) -> Option<usize> {
+    let idx = token.parse::<usize>().ok()?;
+    if idx == 0 || idx > count? { return None; }
+    Some(idx - 1)
+}
+
+/// Extension of the file, ignoring a `.gz` or `.xz` suffix of compressed files.
+fn extension(path: &str) -> Option<String> {
+    let path = Path::new(path);
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    if extension == "gz" || extension == "xz" {
+        return Path::new(path.file_stem()?).extension().map(|e| e.to_string_lossy().to_lowercase());
+    }
+    Some(extension)
+}
@@ -0,0 +1,34 @@
+//! Elimination orderings given as the vertex names in order of elimination, separated by whitespace, commas or line breaks.
+//! Lines starting with `#` or `%` are comments. Every vertex of the graph has to occur exactly once.
+
+use crate::{Graph, Result, TdIsError};
+
+/// Parses an elimination ordering of the vertices of `graph` into vertex indices. `path` is only used in error messages.
+pub fn parse_ordering(content: &str, path: &str, graph: &Graph) -> Result<Vec<usize>> {
+    let mut ordering = Vec::with_capacity(graph.size());
+    let mut seen = vec![false; graph.size()];
+    for (line_idx, line) in content.lines().enumerate() {
+        if line.starts_with('#') || line.starts_with('%') { continue; }
+
+        for name in line.split(|c: char| c.is_whitespace() || c == ',').filter(|t| !t.is_empty()) {
+            let v = match graph.get_vertex_idx(name) {
+                Some(v) => *v,
+                None => return Err(ordering_error(path, Some(line_idx + 1), format!("unknown vertex '{}'", name)))
+            };
+            if seen[v] {
+                return Err(ordering_error(path, Some(line_idx + 1), format!("vertex '{}' is listed twice", name)));
+            }
+            seen[v] = true;
+            ordering.push(v);
+        }
+    }
+
+    if let Some(v) = seen.iter().position(|s| !s) {
+        return Err(ordering_error(path, None, format!("{} of the vertices are missing, e.g. '{}'", graph.size() - ordering.len(), graph.get_vertex_name(v))));
+    }
+    Ok(ordering)
+}
+
+fn ordering_error(path: &str, line: Option<usize>, message: String) -> TdIsError {
+    TdIsError::InvalidOrdering { path: path.to_string(), line, message }
+}
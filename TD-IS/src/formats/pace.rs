@@ -0,0 +1,91 @@
+//! PACE 2017 treewidth formats.
+//!
+//! Graphs (`.gr`) consist of a `p tw <n> <m>` line followed by one `<u> <v>` line per edge, vertices are numbered from 1.
+//! As the format has no vertex weights, they can be given in comment lines `c vw <v> <weight>` anywhere in the file;
+//! all other vertices get a weight by the [`DefaultWeight`] rule.
+//!
+//! Tree decompositions (`.td`) consist of a `s td <bags> <width + 1> <n>` line, one `b <i> <v>...` line per bag
+//! and one `<i> <j>` line per edge between bags, bags are numbered from 1.
+
+use super::{parse_index, DefaultWeight};
+use crate::{Graph, GraphBuilder, InputTreeDecomposition, Result, TdIsError};
+
+/// Parses a graph in PACE `.gr` format. `path` is only used in error messages.
+pub fn parse_graph(content: &str, path: &str, default_weight: DefaultWeight) -> Result<Graph> {
+    let mut builder = GraphBuilder::new();
+    let mut num_vertices = None;
+    let mut weights = Vec::new();   // Weight comments, applied once the header has given the number of vertices
+    for (line_idx, line) in content.lines().enumerate() {
+        let malformed = || TdIsError::MalformedLine { path: path.to_string(), line: line_idx + 1, content: line.to_string() };
+        let tokens = line.split_whitespace().collect::<Vec<&str>>();
+        match tokens.as_slice() {
+            [] => continue,
+            ["c", "vw", v, w] => {
+                let weight = w.parse::<usize>().map_err(|_| TdIsError::BadWeight { path: path.to_string(), line: line_idx + 1, value: w.to_string() })?;
+                weights.push((line_idx, line, *v, weight));
+            },
+            ["c", ..] => continue,
+            ["p", "tw", n, _] if num_vertices.is_none() => {
+                let n = n.parse::<usize>().map_err(|_| malformed())?;
+                for v in 0..n {
+                    builder.add_vertex(&(v + 1).to_string(), default_weight.weight(v));
+                }
+                num_vertices = Some(n);
+            },
+            [u, v] => {
+                let u = parse_index(u, num_vertices).ok_or_else(malformed)?;
+                let v = parse_index(v, num_vertices).ok_or_else(malformed)?;
+                builder.add_edge(u, v);
+            },
+            _ => return Err(malformed())
+        }
+    }
+    for (line_idx, line, v, weight) in weights {
+        let v = parse_index(v, num_vertices)
+            .ok_or_else(|| TdIsError::MalformedLine { path: path.to_string(), line: line_idx + 1, content: line.to_string() })?;
+        builder.set_weight(v, weight);
+    }
+
+    Ok(builder.build())
+}
+
+/// Parses a tree decomposition of `graph` in PACE `.td` format. `path` is only used in error messages.
+pub fn parse_td(content: &str, path: &str, graph: &Graph) -> Result<InputTreeDecomposition> {
+    let mut bags = Vec::new();
+    let mut edges = Vec::new();
+    let mut num_bags = None;
+    for (line_idx, line) in content.lines().enumerate() {
+        let malformed = || TdIsError::MalformedLine { path: path.to_string(), line: line_idx + 1, content: line.to_string() };
+        let tokens = line.split_whitespace().collect::<Vec<&str>>();
+        match tokens.as_slice() {
+            [] | ["c", ..] => continue,
+            ["s", "td", b, _, n] if num_bags.is_none() => {
+                let b = b.parse::<usize>().map_err(|_| malformed())?;
+                if n.parse::<usize>().ok() != Some(graph.size()) {
+                    return Err(TdIsError::InvalidTd(format!("decomposition is for {} vertices, but the graph has {}", n, graph.size())));
+                }
+                bags = vec![Vec::new(); b];
+                edges = vec![Vec::new(); b];
+                num_bags = Some(b);
+            },
+            ["b", i, vertices @ ..] => {
+                let i = parse_index(i, num_bags).ok_or_else(malformed)?;
+                for v in vertices {
+                    match graph.get_vertex_idx(v) {
+                        Some(idx) => bags[i].push(*idx),
+                        None => return Err(TdIsError::UnknownVertex { path: path.to_string(), line: line_idx + 1, bag: (i + 1).to_string(), vertex: v.to_string() })
+                    }
+                }
+            },
+            [i, j] => {
+                let i = parse_index(i, num_bags).ok_or_else(malformed)?;
+                let j = parse_index(j, num_bags).ok_or_else(malformed)?;
+                edges[i].push(j);
+            },
+            _ => return Err(malformed())
+        }
+    }
+
+    let bag_names = (1..=bags.len()).map(|i| i.to_string()).collect();
+    InputTreeDecomposition::from_named_bags(bag_names, bags, edges, graph)
+}
@@ -0,0 +1,22 @@
+//! Line-oriented solution files in the style of the PACE challenges (`.sol`).
+//!
+//! A `s mwis <n> <k> <weight>` line gives the number of vertices of the graph, the number of chosen vertices
+//! and the objective value, followed by one line per chosen vertex with its name. The objective value of
+//! every tree of the tree decomposition is given in a `c component <i> <weight>` comment line.
+
+use std::fmt::Write;
+
+use crate::{Graph, Solution};
+
+/// Writes a solution of `graph` as solution file.
+pub fn write_solution(solution: &Solution, graph: &Graph) -> String {
+    let mut output = String::new();
+    for (i, weight) in solution.component_weights().iter().enumerate() {
+        writeln!(output, "c component {} {}", i + 1, weight).unwrap();
+    }
+    writeln!(output, "s mwis {} {} {}", graph.size(), solution.vertices().len(), solution.weight()).unwrap();
+    for name in solution.vertex_names().iter() {
+        writeln!(output, "{}", name).unwrap();
+    }
+    output
+}
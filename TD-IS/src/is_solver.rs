@@ -1,43 +1,92 @@
 use bit_vec::BitVec;
-use itertools::Itertools;
 
 use super::*;
+use crate::error::{Result, TdIsError};
 
 
+/// Dynamic programming solver for the maximum weight independent set problem on nice tree decompositions.
 pub struct ISSolver {}
 
 impl ISSolver {
-    pub fn solve(td: &TreeDecomposition, graph: &Graph) -> usize {
-        let mut finished_leaves = BitVec::from_elem(td.leaves().len(), false);
-        
-        while !finished_leaves.all() {
-            let current_leaf = finished_leaves.iter().enumerate().find(|(_, b)| !*b).unwrap().0;
-            let mut current_node = td.leaves()[current_leaf];
-            // Traverse from leaf to next unprocessed join (or root)
-            while td.get_node(current_node).can_traverse(&td) {
-                td.get_node_mut(current_node).update_entries(graph, td);
-                current_node = td.get_node(current_node).next();
-            }
-            finished_leaves.set(current_leaf, true);
+    /// Solves the instance given by `graph` and its nice tree decompositions and retrieves an optimal independent set.
+    /// Every tree is solved on its own, the solutions are merged into one independent set.
+    pub fn run(tds: &[TreeDecomposition], graph: &Graph) -> Result<Solution> {
+        Self::run_with_options(tds, graph, &SolverOptions::default())
+    }
+
+    /// Like [`ISSolver::run`], but with the given solver options.
+    pub fn run_with_options(tds: &[TreeDecomposition], graph: &Graph, options: &SolverOptions) -> Result<Solution> {
+        let mut component_weights = Vec::new();
+        let mut vertices = Vec::new();
+        for td in tds {
+            let obj_val = Self::solve(td, graph, options)?;
+            vertices.extend(Self::retrieve_solutions(td, graph, obj_val, options)?);
+            component_weights.push(obj_val);
         }
+        vertices.sort();
+        Ok(Solution::new(vertices, component_weights, graph))
+    }
 
-        match td.get_node(1).max_is().iter().max_by_key(|(_, obj_val)| **obj_val) {
-            Some(x) => *x.1,
-            None => {
-                eprint!("No obj val at root");
-                0
+    /// Fills the DP tables of all nodes of `td` and returns the weight of a maximum independent set.
+    /// Tables and decisions left from an earlier solve are discarded first.
+    pub fn solve(td: &TreeDecomposition, graph: &Graph, options: &SolverOptions) -> Result<usize> {
+        for idx in 0..td.len() {
+            td.get_node_mut(idx).reset();
+        }
+        Self::fill_tables(td, graph, options, td.leaves(), 1);
+
+        match td.get_node(1).max_is().iter().map(|(_, obj_val)| obj_val).max() {
+            Some(x) => Ok(x),
+            None => Err(TdIsError::InvalidTd("no objective value at the root".to_string()))
+        }
+    }
+
+    /// Fills the DP tables of the subtree below (and including) node `top`, starting from its `leaves`.
+    fn fill_tables(td: &TreeDecomposition, graph: &Graph, options: &SolverOptions, leaves: &[usize], top: usize) {
+        for leaf in leaves {
+            let mut current_node = *leaf;
+            // Traverse from leaf to next unprocessed join (or top)
+            while td.get_node(current_node).can_traverse(td) {
+                td.get_node_mut(current_node).update_entries(graph, td, options);
+                if options.free_tables {
+                    td.get_node(current_node).prev().iter().for_each(|i| td.get_node_mut(*i).clear_table());
+                }
+                if current_node == top { break; }
+                current_node = td.get_node(current_node).next();
             }
         }
     }
 
-    pub fn retrieve_solutions(td: &TreeDecomposition, graph: &Graph, obj_val: usize) -> String {
-        let mut solution = Vec::new();
-        let mut rejected = BitVec::from_elem(graph.size(), false);
-        td.get_node(1).get_solution(&mut solution, &mut rejected, obj_val, graph, td);
-        solution.sort();
-        // println!("rej: {:?}", rejected);
-        assert!(graph.is_is(&solution));
-        assert_eq!(graph.get_weight_of_set(&solution), obj_val);
-        solution.iter().map(|v| graph.get_vertex_name(*v)).join(",")
+    /// Retrieves the (sorted) vertices of an independent set of weight `obj_val` from the solved tables of `td`
+    /// by walking down from a root entry of that weight to the entries it was computed from.
+    pub fn retrieve_solutions(td: &TreeDecomposition, graph: &Graph, obj_val: usize, options: &SolverOptions) -> Result<Vec<usize>> {
+        let root_mask = match td.get_node(1).max_is().iter().find(|(_, w)| *w == obj_val) {
+            Some((mask, _)) => mask,
+            None => return Err(TdIsError::InvalidTd(format!("no entry of weight {} at the root", obj_val)))
+        };
+
+        let mut in_solution = BitVec::from_elem(graph.size(), false);
+        let mut entries = vec![(1, root_mask)];
+        while let Some((node_idx, mask)) = entries.pop() {
+            let node = td.get_node(node_idx);
+            node.bag().subset(mask).for_each(|v| in_solution.set(v, true));
+            let recompute = node.needs_prev_table() && td.get_node(node.prev()[0]).max_is().is_empty();
+            if recompute {  // Table was freed, recompute the subtree below once and record its decisions this time
+                let options = SolverOptions { record_decisions: true, ..*options };
+                Self::fill_tables(td, graph, &options, &td.leaves_below(node.prev()[0]), node.prev()[0]);
+            }
+            entries.extend(node.prev_entries(mask, td));
+            if recompute {
+                td.get_node_mut(node.prev()[0]).clear_table();
+            }
+        }
+        let solution = in_solution.iter().enumerate().filter(|(_, b)| *b).map(|(v, _)| v).collect::<Vec<usize>>();
+        if !graph.is_is(&solution) {
+            return Err(TdIsError::InvalidTd("retrieved vertices do not form an independent set".to_string()));
+        }
+        if graph.get_weight_of_set(&solution) != obj_val {
+            return Err(TdIsError::InvalidTd(format!("retrieved set has weight {} instead of {}", graph.get_weight_of_set(&solution), obj_val)));
+        }
+        Ok(solution)
     }
 }
\ No newline at end of file
@@ -0,0 +1,68 @@
+//! Maximum weight independent set solver based on dynamic programming over tree decompositions.
+//!
+//! A [`Graph`] and a tree decomposition of it are turned into one nice [`TreeDecomposition`]
+//! per tree of the input decomposition, which [`ISSolver`] then solves.
+//! If no tree decomposition is at hand, one can be computed with [`decomposition`].
+//! All fallible operations return a [`TdIsError`].
+//!
+//! ```
+//! use td_is::{Graph, ISSolver, TreeDecomposition};
+//!
+//! let graph = Graph::new("instances/MiniG.csv")?;
+//! let tds = TreeDecomposition::new("instances/MiniTD.csv", &graph)?;
+//! let solution = ISSolver::run(&tds, &graph)?;
+//!
+//! assert_eq!(solution.weight(), 10);
+//! assert_eq!(solution.vertex_names(), &vec!["1", "3", "4"]);
+//! # Ok::<(), td_is::TdIsError>(())
+//! ```
+//!
+//! Graphs can also be constructed in memory with a [`GraphBuilder`]:
+//!
+//! ```
+//! use td_is::GraphBuilder;
+//!
+//! let mut builder = GraphBuilder::new();
+//! let a = builder.add_vertex("a", 3);
+//! let b = builder.add_vertex("b", 2);
+//! builder.add_edge(a, b);
+//! let graph = builder.build();
+//!
+//! assert_eq!(graph.size(), 2);
+//! assert!(!graph.is_is(&[a, b]));
+//! ```
+//!
+//! Likewise, a tree decomposition can be passed as bags of vertex indices plus an adjacency list over the bags:
+//!
+//! ```
+//! use td_is::{GraphBuilder, ISSolver, TreeDecomposition};
+//!
+//! let mut builder = GraphBuilder::new();
+//! let a = builder.add_vertex("a", 3);
+//! let b = builder.add_vertex("b", 2);
+//! let c = builder.add_vertex("c", 2);
+//! builder.add_edge(a, b);
+//! builder.add_edge(b, c);
+//! let graph = builder.build();
+//!
+//! let tds = TreeDecomposition::from_bags(vec![vec![a, b], vec![b, c]], vec![vec![1], vec![]], &graph)?;
+//! let solution = ISSolver::run(&tds, &graph)?;
+//!
+//! assert_eq!(solution.weight(), 5);
+//! assert_eq!(solution.vertex_names(), &vec!["a", "c"]);
+//! # Ok::<(), td_is::TdIsError>(())
+//! ```
+
+mod datastructures;
+pub mod decomposition;
+pub mod formats;
+mod error;
+mod is_solver;
+mod solution;
+mod solver_options;
+
+pub use datastructures::{Graph, GraphBuilder, InputTreeDecomposition, TdViolation, TreeDecomposition};
+pub use error::{Result, TdIsError};
+pub use is_solver::ISSolver;
+pub use solution::Solution;
+pub use solver_options::SolverOptions;
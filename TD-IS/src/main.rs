@@ -1,21 +1,294 @@
-mod datastructures;
-mod is_solver;
+use std::time::{Duration, Instant};
+use std::{env, fs, process};
 
-use std::env;
+use itertools::Itertools;
 
-use datastructures::{TreeDecomposition, Graph};
-use is_solver::ISSolver;
+use td_is::decomposition::{self, Heuristic};
+use td_is::formats::{self, OutputFormat, ReadOptions, RunStats};
+use td_is::{ISSolver, Result, SolverOptions, TdIsError, TreeDecomposition};
 
 
+const USAGE: &str = "Usage: TD-IS [options] <graph> <td>
+       TD-IS [options] <instance.json>
+       TD-IS [options] --load-td <nice-td> <graph>
+       TD-IS [options] <graph>    (computes a tree decomposition)
+
+Inputs may be gzip or xz compressed, '-' reads an input from stdin.
+
+Options:
+    --verbose             Report the size, width and number of DP table entries of every tree of the nice tree decomposition
+    --validate            Only check whether <td> is a tree decomposition of <graph> and report all violations
+    --record-decisions    Record the decisions of forget nodes during solving and follow them during retrieval
+    --free-tables         Drop DP tables as soon as they are no longer needed for solving
+    --graph-format <format>
+                          Format of <graph>: csv, pace, dimacs, metis, edgelist, mtx or graphml (default: detected)
+    --default-weight <rule>
+                          Weight of vertices without a given weight: unit (default) or mod200
+    --weight-attribute <name>
+                          GraphML node attribute holding the vertex weights (default: weight)
+    --label-attribute <name>
+                          GraphML node attribute naming the vertices (default: node id)
+    --format <format>     Format of the solution: text, json or sol
+                          (default: detected from --output, json for JSON instances, text otherwise)
+    --output <path>       Write the solution to <path> instead of stdout
+    --dot <path>          Write the nice tree decomposition as Graphviz DOT graph to <path>
+    --dot-tables          Label the nodes in the DOT graph with the size of their DP table and best weight
+    --save-td <path>      Save the nice tree decomposition to <path>
+    --load-td <path>      Load a nice tree decomposition saved with --save-td instead of reading <td>
+    --heuristic <name>    Heuristic for computing a tree decomposition if no <td> is given:
+                          min-degree, min-fill (default) or mcs
+    --exact               Compute a tree decomposition of minimum width if no <td> is given
+    --exact-timeout <seconds>
+                          Time limit of --exact, after which the best heuristic decomposition is used (default: 10)
+    --exact-max-sets <count>
+                          Number of vertex sets (about 100 bytes each) --exact may store before giving up like on
+                          a timeout (default: 1048576)
+    --simplify            Contract bags of the tree decomposition that are subsets of a neighboring bag before solving
+    --ordering <path>     Derive the tree decomposition from the elimination ordering of vertex names in <path>
+                          instead of reading <td>";
+
+/// Where the tree decomposition comes from.
+enum TdSource {
+    /// A file with an input tree decomposition.
+    File(String),
+    /// The JSON instance that also contains the graph. Read from stdin, the input may also turn out to be
+    /// a plain graph, whose tree decomposition is then computed with the default heuristic.
+    Instance,
+    /// A file with a nice tree decomposition saved before.
+    NiceTd(String),
+    /// Computed from the graph.
+    Heuristic(Heuristic),
+    /// Computed from the graph with minimum width, within the given time and number of stored sets.
+    Exact(Duration, usize),
+    /// Derived from an elimination ordering in a file.
+    Ordering(String)
+}
+
+struct Options {
+    graph_path: String,
+    td_source: TdSource,
+    validate_only: bool,
+    verbose: bool,
+    output_format: Option<OutputFormat>,
+    output_path: Option<String>,
+    dot_path: Option<String>,
+    dot_tables: bool,
+    save_td_path: Option<String>,
+    simplify: bool,
+    read_options: ReadOptions,
+    solver_options: SolverOptions
+}
+
+impl Options {
+    fn parse(args: &[String]) -> std::result::Result<Self, String> {
+        let mut paths = Vec::new();
+        let mut validate_only = false;
+        let mut verbose = false;
+        let mut output_format = None;
+        let mut output_path = None;
+        let mut dot_path = None;
+        let mut dot_tables = false;
+        let mut save_td_path = None;
+        let mut load_td_path = None;
+        let mut ordering_path = None;
+        let mut simplify = false;
+        let mut heuristic = None;
+        let mut exact = false;
+        let mut exact_timeout = Duration::from_secs(10);
+        let mut exact_max_sets = decomposition::DEFAULT_MAX_SETS;
+        let mut read_options = ReadOptions::default();
+        let mut solver_options = SolverOptions::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+            match arg.as_str() {
+                "--validate" => validate_only = true,
+                "--verbose" => verbose = true,
+                "--record-decisions" => solver_options.record_decisions = true,
+                "--free-tables" => solver_options.free_tables = true,
+                "--format" => output_format = Some(value()?.parse()?),
+                "--output" => output_path = Some(value()?.clone()),
+                "--dot" => dot_path = Some(value()?.clone()),
+                "--dot-tables" => dot_tables = true,
+                "--save-td" => save_td_path = Some(value()?.clone()),
+                "--load-td" => load_td_path = Some(value()?.clone()),
+                "--ordering" => ordering_path = Some(value()?.clone()),
+                "--simplify" => simplify = true,
+                "--heuristic" => heuristic = Some(value()?.parse()?),
+                "--exact" => exact = true,
+                "--exact-timeout" => {
+                    let seconds = value()?.parse::<f64>().map_err(|e| format!("Invalid timeout: {}", e))?;
+                    exact_timeout = Duration::try_from_secs_f64(seconds).map_err(|e| format!("Invalid timeout: {}", e))?;
+                },
+                "--exact-max-sets" => exact_max_sets = value()?.parse().map_err(|e| format!("Invalid number of sets: {}", e))?,
+                "--graph-format" => read_options.graph_format = Some(value()?.parse()?),
+                "--default-weight" => read_options.default_weight = value()?.parse()?,
+                "--weight-attribute" => read_options.weight_attribute = Some(value()?.clone()),
+                "--label-attribute" => read_options.label_attribute = Some(value()?.clone()),
+                _ if arg.starts_with("--") => return Err(format!("Unknown option {}", arg)),
+                _ => paths.push(arg.clone())
+            }
+        }
+        let td_source = match (paths.len(), load_td_path, ordering_path, heuristic, exact) {
+            (1, Some(path), None, None, false) => TdSource::NiceTd(path),
+            (1, None, Some(path), None, false) => TdSource::Ordering(path),
+            (1, None, None, None, false) if formats::is_instance(&paths[0]) || paths[0] == formats::STDIN_PATH => TdSource::Instance,
+            (1, None, None, None, true) => TdSource::Exact(exact_timeout, exact_max_sets),
+            (1, None, None, heuristic, false) => TdSource::Heuristic(heuristic.unwrap_or_default()),
+            (2, None, None, None, false) => TdSource::File(paths[1].clone()),
+            (0, ..) => return Err("Path to input graph not specified!".to_string()),
+            (1 | 2, ..) => return Err("Only one of <td>, --load-td, --ordering, --heuristic and --exact can be given".to_string()),
+            _ => return Err(format!("Too many paths given: {}", paths.join(" ")))
+        };
+        if validate_only && !matches!(td_source, TdSource::File(_) | TdSource::Instance) {
+            return Err("--validate checks an input TD, which is not given".to_string());
+        }
+        if simplify && matches!(td_source, TdSource::NiceTd(_)) {
+            return Err("--simplify works on input TDs, it cannot be combined with --load-td".to_string());
+        }
+        let td_path = match &td_source {
+            TdSource::File(path) | TdSource::NiceTd(path) | TdSource::Ordering(path) => Some(path),
+            _ => None
+        };
+        if paths.iter().take(1).chain(td_path).filter(|path| *path == formats::STDIN_PATH).count() > 1 {
+            return Err(format!("Only one input can be read from stdin ({})", formats::STDIN_PATH));
+        }
+
+        let output_format = output_format.or_else(|| output_path.as_deref().and_then(OutputFormat::from_extension));
+
+        Ok(Options {
+            graph_path: paths[0].clone(),
+            td_source,
+            validate_only,
+            verbose,
+            output_format,
+            output_path,
+            dot_path,
+            dot_tables,
+            save_td_path,
+            simplify,
+            read_options,
+            solver_options
+        })
+    }
+}
+
 fn main() {
-    let args = env::args().collect::<Vec<String>>();
-    if args.len() < 3 {
-        eprintln!("Paths to input graph and input TD not specified!");
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let options = match Options::parse(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    match run(&options) {
+        Ok(true) => {},
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Runs the command given by `options`, returns whether the tree decomposition was valid.
+fn run(options: &Options) -> Result<bool> {
+    let start = Instant::now();
+    let (graph, input_td) = match &options.td_source {
+        TdSource::File(td_path) => {
+            let graph = formats::read_graph(&options.graph_path, &options.read_options)?;
+            let input_td = formats::read_td(td_path, &graph)?;
+            (graph, Some(input_td))
+        },
+        TdSource::Instance => formats::read_graph_or_instance(&options.graph_path, &options.read_options)?,
+        TdSource::NiceTd(_) | TdSource::Heuristic(_) | TdSource::Exact(..) | TdSource::Ordering(_) => (formats::read_graph(&options.graph_path, &options.read_options)?, None)
+    };
+    let parse_time = start.elapsed();
+    let json_input = matches!(options.td_source, TdSource::Instance) && input_td.is_some();
+
+    if options.validate_only && input_td.is_none() {
+        eprintln!("--validate checks an input TD, but {} is a graph without one", options.graph_path);
+        return Ok(false);
+    }
+    if let (true, Some(input_td)) = (options.validate_only, &input_td) {
+        let violations = input_td.validate(&graph);
+        for violation in violations.iter() {
+            println!("{}", violation);
+        }
+        println!("{}", if violations.is_empty() { "valid" } else { "invalid" });
+        return Ok(violations.is_empty());
     }
-    let graph = Graph::new(&args[1]);
-    let tds = TreeDecomposition::new(&args[2], &graph);
-    let total_obj_val = tds.iter().fold(0, |acc, td| acc + ISSolver::solve(td, &graph));
-    let solution = ISSolver::retrieve_solutions(&tds[0], &graph, total_obj_val);
 
-    println!("obj: {}\n{}", total_obj_val, solution);
+    let start = Instant::now();
+    let tds = match (input_td, &options.td_source) {
+        (None, TdSource::NiceTd(path)) => TreeDecomposition::load(path, &graph)?,
+        (input_td, source) => {
+            let mut input_td = match (input_td, source) {
+                (Some(input_td), _) => input_td,
+                (None, TdSource::Heuristic(_) | TdSource::Instance) => {
+                    let heuristic = match source {
+                        TdSource::Heuristic(heuristic) => *heuristic,
+                        _ => Heuristic::default()
+                    };
+                    let input_td = decomposition::decompose(&graph, heuristic)?;
+                    eprintln!("Computed treedecomposition of width {} with {:?} heuristic", input_td.width(), heuristic);
+                    input_td
+                },
+                (None, TdSource::Ordering(path)) => {
+                    let input_td = decomposition::from_ordering(&graph, &formats::read_ordering(path, &graph)?)?;
+                    eprintln!("Derived treedecomposition of width {} from elimination ordering", input_td.width());
+                    input_td
+                },
+                (None, TdSource::Exact(timeout, max_sets)) => {
+                    let exact = decomposition::decompose_exact(&graph, *timeout, *max_sets)?;
+                    let kind = if exact.is_optimal() {
+                        "optimal".to_string()
+                    } else {
+                        format!("best heuristic, {}", exact.fallbacks.iter().unique().join(", "))
+                    };
+                    eprintln!("Computed treedecomposition of width {} ({})", exact.td.width(), kind);
+                    exact.td
+                },
+                (None, _) => unreachable!("input TDs are read above")
+            };
+            if options.simplify {
+                let simplified = input_td.simplify(&graph)?;
+                eprintln!("Simplified treedecomposition from {} bags of width {} to {} bags of width {}",
+                    input_td.len(), input_td.width(), simplified.len(), simplified.width());
+                input_td = simplified;
+            }
+            TreeDecomposition::from_input(&input_td, &graph)?
+        }
+    };
+    let decomposition_time = start.elapsed();
+    if options.verbose {
+        for (i, td) in tds.iter().enumerate() {
+            eprintln!("Tree {}: {} nodes, width {}, table entries {:.0}", i + 1, td.len() - 1, td.width(), td.estimated_cost(&graph));
+        }
+        eprintln!("Table entries: {:.0}", tds.iter().map(|td| td.estimated_cost(&graph)).sum::<f64>());
+    }
+    if let Some(path) = &options.save_td_path {
+        TreeDecomposition::save(&tds, &graph, path)?;
+    }
+
+    let start = Instant::now();
+    let result = ISSolver::run_with_options(&tds, &graph, &options.solver_options);
+    let solve_time = start.elapsed();
+    if let Some(path) = &options.dot_path {
+        let dot = TreeDecomposition::forest_to_dot(&tds, &graph, options.dot_tables);    // Also written if solving failed, for debugging
+        fs::write(path, dot).map_err(|source| TdIsError::Output { path: path.clone(), source })?;
+    }
+    let solution = result?;
+
+    let width = tds.iter().map(|td| td.width()).max().unwrap_or(0);
+    let stats = RunStats { width, parse_time, decomposition_time, solve_time };
+    let output_format = options.output_format.unwrap_or(if json_input { OutputFormat::Json } else { OutputFormat::Text });    // JSON in, JSON out
+    let output = formats::write_solution(output_format, &solution, &graph, &stats);
+    match &options.output_path {
+        Some(path) => fs::write(path, output).map_err(|source| TdIsError::Output { path: path.clone(), source })?,
+        None => print!("{}", output)
+    }
+    Ok(true)
 }
@@ -0,0 +1,54 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use super::Graph;
+
+/// A maximum weight independent set together with its total weight.
+#[derive(Debug, Clone)]
+pub struct Solution {
+    weight: usize,
+    vertices: Vec<usize>,
+    vertex_names: Vec<String>,
+    component_weights: Vec<usize>
+}
+
+impl Solution {
+    /// Creates a solution from the (sorted) vertex indices of an independent set of `graph`
+    /// and the weights of its parts in the individual trees of the tree decomposition.
+    pub fn new(vertices: Vec<usize>, component_weights: Vec<usize>, graph: &Graph) -> Self {
+        let vertex_names = vertices.iter().map(|v| graph.get_vertex_name(*v).clone()).collect();
+        Solution {
+            weight: component_weights.iter().sum(),
+            vertices,
+            vertex_names,
+            component_weights
+        }
+    }
+
+    /// Total weight of the independent set, i.e. the objective value.
+    pub fn weight(&self) -> usize {
+        self.weight
+    }
+
+    /// Graph indices of the vertices in the independent set.
+    pub fn vertices(&self) -> &Vec<usize> {
+        &self.vertices
+    }
+
+    /// Names of the vertices in the independent set, in the same order as [`Solution::vertices`].
+    pub fn vertex_names(&self) -> &Vec<String> {
+        &self.vertex_names
+    }
+
+    /// Objective value of every tree of the tree decomposition, in the order the trees were solved.
+    pub fn component_weights(&self) -> &Vec<usize> {
+        &self.component_weights
+    }
+}
+
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "obj: {}\n{}", self.weight, self.vertex_names.iter().join(","))
+    }
+}
@@ -0,0 +1,11 @@
+/// Options controlling how [`crate::ISSolver`] fills and keeps its DP tables.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SolverOptions {
+    /// Record for every entry of a forget node whether the forgotten vertex was taken.
+    /// Retrieval then follows these decisions instead of comparing the entries of the previous node.
+    pub record_decisions: bool,
+    /// Drop the table of a node as soon as the next node has been computed, keeping only the tables at the root.
+    /// Without recorded decisions, retrieval recomputes the tables below the topmost forget nodes it descends through,
+    /// recording the decisions of that subtree.
+    pub free_tables: bool
+}
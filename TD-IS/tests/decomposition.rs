@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use td_is::decomposition::{decompose_exact, Fallback, DEFAULT_MAX_SETS};
+use td_is::{Graph, GraphBuilder};
+
+fn graph(n: usize, edges: &[(usize, usize)]) -> Graph {
+    let mut builder = GraphBuilder::new();
+    for v in 0..n {
+        builder.add_vertex(&v.to_string(), 1);
+    }
+    for (u, v) in edges {
+        builder.add_edge(*u, *v);
+    }
+    builder.build()
+}
+
+fn petersen() -> Graph {
+    let mut edges = Vec::new();
+    for i in 0..5 {
+        edges.push((i, (i + 1) % 5));       // Outer cycle
+        edges.push((i, i + 5));             // Spokes
+        edges.push((i + 5, (i + 2) % 5 + 5));   // Inner pentagram
+    }
+    graph(10, &edges)
+}
+
+fn grid(size: usize) -> Graph {
+    let mut edges = Vec::new();
+    for row in 0..size {
+        for col in 0..size {
+            let v = row * size + col;
+            if col + 1 < size { edges.push((v, v + 1)); }
+            if row + 1 < size { edges.push((v, v + size)); }
+        }
+    }
+    graph(size * size, &edges)
+}
+
+#[test]
+fn exact_width_of_graphs_with_known_treewidth() {
+    for (graph, treewidth) in [(petersen(), 4), (grid(5), 5)] {
+        let exact = decompose_exact(&graph, Duration::from_secs(60), DEFAULT_MAX_SETS).unwrap();
+        assert!(exact.is_optimal());
+        assert!(exact.td.validate(&graph).is_empty());
+        assert_eq!(exact.td.width(), treewidth);
+    }
+}
+
+#[test]
+fn exact_search_skips_components_with_more_than_128_vertices() {
+    let path = graph(129, &(1..129).map(|v| (v - 1, v)).collect::<Vec<_>>());
+    let exact = decompose_exact(&path, Duration::from_secs(60), DEFAULT_MAX_SETS).unwrap();
+    assert_eq!(exact.fallbacks, vec![Fallback::TooLarge(129)]);
+    assert_eq!(exact.td.width(), 1);
+}
+
+#[test]
+fn exact_search_gives_up_after_max_sets() {
+    let exact = decompose_exact(&grid(5), Duration::from_secs(60), 100).unwrap();
+    assert_eq!(exact.fallbacks, vec![Fallback::TooManySets]);
+    assert!(exact.td.validate(&grid(5)).is_empty());
+}
@@ -0,0 +1,35 @@
+use td_is::formats::{pace, DefaultWeight, GraphFormat};
+use td_is::TdIsError;
+
+#[test]
+fn detects_c_comments_only_as_whole_token() {
+    assert_eq!(GraphFormat::from_header("c comment\np tw 2 1\n1 2\n"), Some(GraphFormat::Pace));
+    assert_eq!(GraphFormat::from_header("c\np edge 2 1\ne 1 2\n"), Some(GraphFormat::Dimacs));
+    assert_eq!(GraphFormat::from_header("cat,,1\ndog,,2\ncat,dog\n"), Some(GraphFormat::Csv));
+    assert_eq!(GraphFormat::from_header("cat dog\n"), Some(GraphFormat::EdgeList));
+}
+
+#[test]
+fn tells_metis_from_edge_lists() {
+    assert_eq!(GraphFormat::from_header("3 2 10\n5 2\n1 1 3\n7 2\n"), Some(GraphFormat::Metis));
+    assert_eq!(GraphFormat::from_header("% comment\n4 2\n2\n1 3\n2\n\n"), Some(GraphFormat::Metis));
+    assert_eq!(GraphFormat::from_header("3 2 1\n2 5\n1 5 3 1\n2 1\n"), Some(GraphFormat::Metis));
+    assert_eq!(GraphFormat::from_header("1 2\n2 3\n3 4\n"), Some(GraphFormat::EdgeList));
+    assert_eq!(GraphFormat::from_header("# u v w\n1 2 7\n2 3 1\n"), Some(GraphFormat::EdgeList));
+    assert_eq!(GraphFormat::from_header("1 2\n3\n2 3 4\n"), None);
+}
+
+#[test]
+fn does_not_detect_json_instances_as_graph() {
+    assert_eq!(GraphFormat::from_header("{\"vertices\": [{\"name\": \"a\"}], \"bags\": []}\n"), None);
+    assert_eq!(GraphFormat::from_header("\n{\n    \"vertices\": [],\n    \"bags\": []\n}\n"), None);
+}
+
+#[test]
+fn reads_pace_weight_comments_anywhere() {
+    let graph = pace::parse_graph("c vw 1 5\np tw 3 2\n1 2\nc vw 3 4\n2 3\n", "test.gr", DefaultWeight::Unit).unwrap();
+    assert_eq!((0..3).map(|v| graph.get_weight(v)).collect::<Vec<usize>>(), vec![5, 1, 4]);
+
+    let result = pace::parse_graph("c vw 4 5\np tw 3 2\n1 2\n2 3\n", "test.gr", DefaultWeight::Unit);
+    assert!(matches!(result, Err(TdIsError::MalformedLine { line: 1, .. })));
+}
@@ -0,0 +1,63 @@
+use serde_json::Value;
+use td_is::{Graph, GraphBuilder, ISSolver, TdIsError, TreeDecomposition};
+
+/// Path a-b-c and edge d-e, with weights 1 to 5.
+fn forest() -> (Graph, Vec<TreeDecomposition>) {
+    let mut builder = GraphBuilder::new();
+    for (v, name) in ["a", "b", "c", "d", "e"].iter().enumerate() {
+        builder.add_vertex(name, v + 1);
+    }
+    for (u, v) in [(0, 1), (1, 2), (3, 4)] {
+        builder.add_edge(u, v);
+    }
+    let graph = builder.build();
+    let tds = TreeDecomposition::from_bags(vec![vec![0, 1], vec![1, 2], vec![3, 4]], vec![vec![1], vec![0], vec![]], &graph).unwrap();
+    (graph, tds)
+}
+
+/// Saved forest with `tamper` applied to the nodes of its first tree.
+fn tampered(tamper: impl Fn(&mut Vec<Value>)) -> String {
+    let (graph, tds) = forest();
+    let mut json: Value = serde_json::from_str(&TreeDecomposition::to_json(&tds, &graph)).unwrap();
+    tamper(json["trees"][0]["nodes"].as_array_mut().unwrap());
+    json.to_string()
+}
+
+#[test]
+fn round_trips_forest_through_file() {
+    let (graph, tds) = forest();
+    assert_eq!(tds.len(), 2);
+    let path = std::env::temp_dir().join(format!("td-is-forest-{}.json", std::process::id()));
+    let path = path.to_str().unwrap();
+    TreeDecomposition::save(&tds, &graph, path).unwrap();
+    let loaded = TreeDecomposition::load(path, &graph);
+    std::fs::remove_file(path).unwrap();
+    let loaded = loaded.unwrap();
+
+    assert_eq!(TreeDecomposition::to_json(&loaded, &graph), TreeDecomposition::to_json(&tds, &graph));
+    let solution = ISSolver::run(&loaded, &graph).unwrap();
+    assert_eq!(solution.weight(), ISSolver::run(&tds, &graph).unwrap().weight());
+    assert_eq!(solution.weight(), 9);
+}
+
+#[test]
+fn rejects_node_with_wrong_next() {
+    // Node 2 claims the root as parent, which does not list it as child
+    let content = tampered(|nodes| nodes[2]["next"] = Value::from(0));
+    let (graph, _) = forest();
+    let result = TreeDecomposition::from_json(&content, "test.json", &graph);
+    assert!(matches!(result, Err(TdIsError::InvalidTd(msg)) if msg.contains("does not link back")));
+}
+
+#[test]
+fn rejects_introduce_node_not_matching_its_child() {
+    // The introduced vertex is dropped from the bag, so it equals the bag of the child
+    let content = tampered(|nodes| {
+        let idx = nodes.iter().position(|node| node["type"].get("Introduce").is_some()).unwrap();
+        let child = nodes[idx]["prev"][0].as_u64().unwrap() as usize;
+        nodes[idx]["bag"] = nodes[child]["bag"].clone();
+    });
+    let (graph, _) = forest();
+    let result = TreeDecomposition::from_json(&content, "test.json", &graph);
+    assert!(matches!(result, Err(TdIsError::InvalidTd(msg)) if msg.contains("does not match its bag")));
+}
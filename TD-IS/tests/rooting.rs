@@ -0,0 +1,80 @@
+use td_is::decomposition::{decompose, Heuristic};
+use td_is::{Graph, GraphBuilder, InputTreeDecomposition};
+
+/// Random graph on `n` vertices with edge probability `density`, from a linear congruential generator.
+fn random_graph(n: usize, density: f64, seed: u64) -> Graph {
+    let mut state = seed;
+    let mut random = || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    };
+    let mut builder = GraphBuilder::new();
+    for v in 0..n {
+        builder.add_vertex(&v.to_string(), 1);
+    }
+    for u in 0..n {
+        for v in u + 1..n {
+            if random() < density {
+                builder.add_edge(u, v);
+            }
+        }
+    }
+    builder.build()
+}
+
+/// Cost of rooting the tree of `input_td` containing `root` at it, summed up over the nodes of the nice tree decomposition.
+fn recount(input_td: &InputTreeDecomposition, graph: &Graph, root: usize) -> f64 {
+    let counts = (0..input_td.len()).map(|b| input_td.get_bag(b).count_independent_subsets(graph)).collect::<Vec<f64>>();
+    let table_size = |b: usize, size: usize| 2f64.powi(size as i32).min(counts[b]);
+
+    let mut cost = 0.0;
+    let mut stack = vec![(root, usize::MAX)];
+    while let Some((b, parent)) = stack.pop() {
+        let children = input_td.edges()[b].iter().filter(|c| **c != parent).copied().collect::<Vec<usize>>();
+        cost += match children.len() {
+            0 => counts[b],                     // Leaf
+            c => (c - 1) as f64 * counts[b]     // Join nodes
+        };
+        for child in children {
+            let bag = input_td.get_bag(b).vertices();
+            let child_bag = input_td.get_bag(child).vertices();
+            let common = bag.iter().filter(|v| child_bag.contains(v)).count();
+            cost += (common + 1..=bag.len()).map(|size| table_size(b, size)).sum::<f64>();
+            cost += (common..child_bag.len()).map(|size| table_size(child, size)).sum::<f64>();
+            stack.push((child, b));
+        }
+    }
+    cost
+}
+
+#[test]
+fn rooting_costs_match_recount_for_every_root() {
+    for seed in 0..60 {
+        let n = 5 + (seed as usize * 7) % 25;
+        let density = [0.1, 0.2, 0.4, 0.7][seed as usize % 4];
+        let graph = random_graph(n, density, seed);
+        for heuristic in Heuristic::ALL {
+            let input_td = decompose(&graph, heuristic).unwrap();
+            let costs = input_td.rooting_costs(&graph);
+            for (root, cost) in costs.iter().enumerate() {
+                let expected = recount(&input_td, &graph, root);
+                assert!((cost - expected).abs() <= 1e-9 * expected, "seed {}, root {}: {} instead of {}", seed, root, cost, expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn rooting_costs_count_independent_subsets() {
+    // A clique of 20 vertices in one bag has 21 independent subsets instead of 2^20
+    let mut builder = GraphBuilder::new();
+    for v in 0..20 {
+        builder.add_vertex(&v.to_string(), 1);
+        for u in 0..v {
+            builder.add_edge(u, v);
+        }
+    }
+    let graph = builder.build();
+    let input_td = InputTreeDecomposition::from_bags(vec![(0..20).collect()], vec![], &graph).unwrap();
+    assert_eq!(input_td.rooting_costs(&graph), vec![21.0]);
+}
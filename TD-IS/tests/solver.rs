@@ -0,0 +1,68 @@
+use td_is::{Graph, GraphBuilder, ISSolver, SolverOptions, TdIsError, TreeDecomposition};
+
+/// Clique on `n` vertices with weights 1 to `n`.
+fn clique(n: usize) -> Graph {
+    let mut builder = GraphBuilder::new();
+    for v in 0..n {
+        builder.add_vertex(&v.to_string(), v + 1);
+    }
+    for u in 0..n {
+        for v in u + 1..n {
+            builder.add_edge(u, v);
+        }
+    }
+    builder.build()
+}
+
+#[test]
+fn solves_clique_bags_wider_than_usize() {
+    for n in [63, 64, 65, 128] {
+        let graph = clique(n);
+        let tds = TreeDecomposition::from_bags(vec![(0..n).collect()], vec![], &graph).unwrap();
+        let solution = ISSolver::run(&tds, &graph).unwrap();
+        assert_eq!(solution.weight(), n);
+        assert_eq!(solution.vertices(), &vec![n - 1]);
+
+        let options = SolverOptions { free_tables: true, ..SolverOptions::default() };
+        let tds = TreeDecomposition::from_bags(vec![(0..n).collect()], vec![], &graph).unwrap();
+        assert_eq!(ISSolver::run_with_options(&tds, &graph, &options).unwrap().weight(), n);
+    }
+}
+
+#[test]
+fn rejects_bags_with_more_than_128_vertices() {
+    let graph = clique(129);
+    let result = TreeDecomposition::from_bags(vec![(0..129).collect()], vec![], &graph);
+    assert!(matches!(result, Err(TdIsError::BagTooLarge { size: 129, .. })));
+}
+
+/// Path a-b-c with the given weights.
+fn path(weights: [usize; 3]) -> Graph {
+    let mut builder = GraphBuilder::new();
+    let a = builder.add_vertex("a", weights[0]);
+    let b = builder.add_vertex("b", weights[1]);
+    let c = builder.add_vertex("c", weights[2]);
+    builder.add_edge(a, b);
+    builder.add_edge(b, c);
+    builder.build()
+}
+
+#[test]
+fn solves_same_tree_decomposition_twice() {
+    let first = path([3, 1, 0]);
+    let second = path([10, 3, 10]);
+    let tds = TreeDecomposition::from_bags(vec![vec![0, 1], vec![1, 2]], vec![vec![1]], &first).unwrap();
+    let all_options = [
+        SolverOptions { record_decisions: true, free_tables: false },
+        SolverOptions { record_decisions: false, free_tables: true },
+        SolverOptions::default()
+    ];
+    for first_options in all_options {
+        for second_options in all_options {
+            assert_eq!(ISSolver::run_with_options(&tds, &first, &first_options).unwrap().weight(), 3);
+            let solution = ISSolver::run_with_options(&tds, &second, &second_options).unwrap();
+            assert_eq!(solution.weight(), 20);
+            assert_eq!(solution.vertex_names(), &vec!["a", "c"]);
+        }
+    }
+}